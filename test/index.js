@@ -18,14 +18,16 @@ const scenario = new Scenario([instanceAlice, instanceBob, instanceCharlie], /*{
 const rock = { name: "Rock", wins_against: ["Scissors"], loses_against: ["Paper"] }
 const paper = { name: "Paper", wins_against: ["Rock"], loses_against: ["Scissors"] }
 const scissors = { name: "Scissors", wins_against: ["Paper"], loses_against: ["Rock"] }
-const nonceString = "random"
+const nonceString = "k8Jq2ZxP4mWv7LrT9bNc"
 
 const inputSets = [
   {
     testDescription: "Successful round",
     offerData: {
       challenger_id_: "correct",
-      format_id_: "format"
+      format_id_: "format",
+      reveal_deadline_: 3600,
+      protocol_: "Asymmetric"
     },
     commitmentData: {
       component_: rock,
@@ -39,9 +41,7 @@ const inputSets = [
       challenger_id_: "correct"
     },
     gameResultData: {
-      reveal: { component: rock, nonce: nonceString},
-      move_address: "",
-      host_id: "correct"
+      move_address: ""
     },
     result: "Ok"
   },
@@ -49,7 +49,9 @@ const inputSets = [
     testDescription: "Wrong commitment host",
     offerData: {
       challenger_id_: "correct",
-      format_id_: "format"
+      format_id_: "format",
+      reveal_deadline_: 3600,
+      protocol_: "Asymmetric"
     },
     commitmentData: {
       component_: rock,
@@ -64,8 +66,7 @@ const inputSets = [
     },
     gameResultData: {
       reveal: { component: rock, nonce: nonceString},
-      move_address: "",
-      host_id: "correct"
+      move_address: ""
     },
     result: "SerializationError"
   },
@@ -73,7 +74,9 @@ const inputSets = [
     testDescription: "Wrong move challenger",
     offerData: {
       challenger_id_: "correct",
-      format_id_: "format"
+      format_id_: "format",
+      reveal_deadline_: 3600,
+      protocol_: "Asymmetric"
     },
     commitmentData: {
       component_: rock,
@@ -88,8 +91,7 @@ const inputSets = [
     },
     gameResultData: {
       reveal: { component: rock, nonce: nonceString},
-      move_address: "",
-      host_id: "correct"
+      move_address: ""
     },
     result: "SerializationError"
   },
@@ -97,7 +99,9 @@ const inputSets = [
     testDescription: "Wrong game result host",
     offerData: {
       challenger_id_: "correct",
-      format_id_: "format"
+      format_id_: "format",
+      reveal_deadline_: 3600,
+      protocol_: "Asymmetric"
     },
     commitmentData: {
       component_: rock,
@@ -112,16 +116,18 @@ const inputSets = [
     },
     gameResultData: {
       reveal: { component: rock, nonce: nonceString},
-      move_address: "",
-      host_id: "wrong"
+      move_address: ""
     },
+    gameResultAuthor: "alice",
     result: "SerializationError"
   },
   {
     testDescription: "Wrong commitment author",
     offerData: {
       challenger_id_: "correct",
-      format_id_: "format"
+      format_id_: "format",
+      reveal_deadline_: 3600,
+      protocol_: "Asymmetric"
     },
     commitmentData: {
       component_: rock,
@@ -136,8 +142,7 @@ const inputSets = [
     },
     gameResultData: {
       reveal: { component: rock, nonce: nonceString},
-      move_address: "",
-      host_id: "correct"
+      move_address: ""
     },
     commitmentAuthor: "charlie",
     result: "SerializationError"
@@ -146,7 +151,9 @@ const inputSets = [
     testDescription: "Wrong move author",
     offerData: {
       challenger_id_: "correct",
-      format_id_: "format"
+      format_id_: "format",
+      reveal_deadline_: 3600,
+      protocol_: "Asymmetric"
     },
     commitmentData: {
       component_: rock,
@@ -161,8 +168,7 @@ const inputSets = [
     },
     gameResultData: {
       reveal: { component: rock, nonce: nonceString},
-      move_address: "",
-      host_id: "correct"
+      move_address: ""
     },
     moveAuthor: "charlie",
     result: "SerializationError"
@@ -171,7 +177,9 @@ const inputSets = [
     testDescription: "Wrong game result author",
     offerData: {
       challenger_id_: "correct",
-      format_id_: "format"
+      format_id_: "format",
+      reveal_deadline_: 3600,
+      protocol_: "Asymmetric"
     },
     commitmentData: {
       component_: rock,
@@ -186,8 +194,7 @@ const inputSets = [
     },
     gameResultData: {
       reveal: { component: rock, nonce: nonceString},
-      move_address: "",
-      host_id: "correct"
+      move_address: ""
     },
     gameResultAuthor: "charlie",
     result: "SerializationError"
@@ -196,7 +203,9 @@ const inputSets = [
     testDescription: "Wrong reveal",
     offerData: {
       challenger_id_: "correct",
-      format_id_: "format"
+      format_id_: "format",
+      reveal_deadline_: 3600,
+      protocol_: "Asymmetric"
     },
     commitmentData: {
       component_: rock,
@@ -211,8 +220,7 @@ const inputSets = [
     },
     gameResultData: {
       reveal: { component: rock, nonce: "wrong"},
-      move_address: "",
-      host_id: "correct"
+      move_address: ""
     },
     result: "SerializationError"
   },
@@ -246,12 +254,11 @@ const runTest = async (inputSet) => {
       moveAddress = await alice.callSync("roshambo", "new_move", inputSet.moveData)
     }
   
-    if(inputSet.gameResultData.host_id == "correct") {
-      inputSet.gameResultData.host_id = alice.agentId
-    }
     inputSet.gameResultData.move_address = moveAddress.Ok
     if(inputSet.gameResultAuthor == "charlie") {
       gameResultAddress = await charlie.callSync("roshambo", "new_game_result", inputSet.gameResultData)
+    } else if(inputSet.gameResultAuthor == "alice") {
+      gameResultAddress = await alice.callSync("roshambo", "new_game_result", inputSet.gameResultData)
     } else {
       gameResultAddress = await bob.callSync("roshambo", "new_game_result", inputSet.gameResultData)
     }
@@ -260,4 +267,506 @@ const runTest = async (inputSet) => {
   })
 }
 
-inputSets.forEach((inputSet) => { runTest(inputSet) })
\ No newline at end of file
+inputSets.forEach((inputSet) => { runTest(inputSet) })
+
+// plays an offer, commitment and move, leaving the game waiting on the challenger's reveal
+const playUntilReveal = async ({ alice, bob }, revealDeadline) => {
+  const offerAddress = await alice.callSync("roshambo", "new_offer", {
+    challenger_id_: bob.agentId, format_id_: "format", reveal_deadline_: revealDeadline, protocol_: "Asymmetric"
+  })
+  const commitmentAddress = await bob.callSync("roshambo", "new_commitment", {
+    component_: rock, offer_address_: offerAddress.Ok, host_id_: alice.agentId, nonce_: nonceString
+  })
+  const moveAddress = await alice.callSync("roshambo", "new_move", {
+    component_: paper, commitment_address_: commitmentAddress.Ok, challenger_id_: bob.agentId
+  })
+  return moveAddress.Ok
+}
+
+scenario.runTape("Forfeit claim after reveal deadline", async (t, { alice, bob }) => {
+  const moveAddress = await playUntilReveal({ alice, bob }, 0)
+  const claimAddress = await alice.callSync("roshambo", "new_forfeit_claim", { move_address: moveAddress })
+  t.deepEqual(Object.keys(claimAddress)[0], "Ok")
+
+  const outcome = await alice.callSync("roshambo", "get_outcome", { move_address: moveAddress })
+  t.deepEqual(outcome.Ok.Win.winner_id, alice.agentId)
+})
+
+scenario.runTape("Forfeit claim before reveal deadline", async (t, { alice, bob }) => {
+  const moveAddress = await playUntilReveal({ alice, bob }, 3600)
+  const claimAddress = await alice.callSync("roshambo", "new_forfeit_claim", { move_address: moveAddress })
+  t.deepEqual(Object.keys(claimAddress)[0], "SerializationError")
+})
+
+scenario.runTape("Symmetric round", async (t, { alice, bob }) => {
+  const offerAddress = await alice.callSync("roshambo", "new_offer", {
+    challenger_id_: bob.agentId, format_id_: "format", reveal_deadline_: 3600, protocol_: "Symmetric"
+  })
+  const commitmentAddress = await bob.callSync("roshambo", "new_commitment", {
+    component_: rock, offer_address_: offerAddress.Ok, host_id_: alice.agentId, nonce_: nonceString
+  })
+  const sealedMoveAddress = await alice.callSync("roshambo", "new_sealed_move", {
+    component_: paper, commitment_address_: commitmentAddress.Ok, challenger_id_: bob.agentId, nonce_: nonceString
+  })
+  await bob.callSync("roshambo", "new_move_reveal", {
+    reveal: { component: rock, nonce: nonceString }, move_address: sealedMoveAddress.Ok
+  })
+  await alice.callSync("roshambo", "new_move_reveal", {
+    reveal: { component: paper, nonce: nonceString }, move_address: sealedMoveAddress.Ok
+  })
+  const gameResultAddress = await alice.callSync("roshambo", "new_symmetric_game_result", { move_address: sealedMoveAddress.Ok })
+  t.deepEqual(Object.keys(gameResultAddress)[0], "Ok")
+
+  const outcome = await bob.callSync("roshambo", "get_outcome", { move_address: sealedMoveAddress.Ok })
+  t.deepEqual(outcome.Ok.Win.winner_id, alice.agentId)
+})
+
+scenario.runTape("Arbiter publishes result for stalled challenger", async (t, { alice, bob, charlie }) => {
+  const offerAddress = await alice.callSync("roshambo", "new_offer", {
+    challenger_id_: bob.agentId, format_id_: "format", reveal_deadline_: 0, protocol_: "Asymmetric", arbiter_id_: charlie.agentId
+  })
+  const commitmentAddress = await bob.callSync("roshambo", "new_commitment", {
+    component_: rock, offer_address_: offerAddress.Ok, host_id_: alice.agentId, nonce_: nonceString
+  })
+  const moveAddress = await alice.callSync("roshambo", "new_move", {
+    component_: paper, commitment_address_: commitmentAddress.Ok, challenger_id_: bob.agentId
+  })
+  const claimAddress = await alice.callSync("roshambo", "new_forfeit_claim", { move_address: moveAddress.Ok })
+  t.deepEqual(Object.keys(claimAddress)[0], "SerializationError")
+
+  const gameResultAddress = await charlie.callSync("roshambo", "new_arbitrated_game_result", { move_address: moveAddress.Ok })
+  t.deepEqual(Object.keys(gameResultAddress)[0], "Ok")
+})
+
+// A 2048 bit test modulus; its totient is public here, so it only suits tests
+const testModulus = "24104152370721538489196937800706586844631490636016945712403087266581492483357640674886773978177305576048156888874324557432931843953246758589013415778286124965546606277602865934540561470328617633874293343533242224745239162190999356240686518124793566430156042192661014462497393555213636116814439293339348130105974227860086951589023611813152831351453666406603806030849887550001279351599279367569634874206402074009709824600899000595079653599236591061693156218265332948806288896365971459365261370607330611749677506273400525817213885104744233276587678104230021164768662973860980818754873459066898160226732279271536044685001"
+const testTotient = "24104152370721538489196937800706586844631490636016945712403087266581492483357640674886773978177305576048156888874324557432931843953246758589013415778286124965546606277602865934540561470328617633874293343533242224745239162190999356240686518124793566430156042192661014462497393555213636116814439293339348130105661711752170132483600554013047696045710918717415684508732442502468035355916507466381733557128589179218406933532271604768218088274028616175262286125660734117133466733563162715129586228971433033872957649548019589626187942565208865057789858735614770644648755052370336530336913981843526713879135446011946189215172"
+
+scenario.runTape("Anyone opens a time-locked commitment after the deadline", async (t, { alice, bob, charlie }) => {
+  const offerAddress = await alice.callSync("roshambo", "new_offer", {
+    challenger_id_: bob.agentId, format_id_: "format", reveal_deadline_: 1, protocol_: "Asymmetric"
+  })
+  const commitmentAddress = await bob.callSync("roshambo", "new_commitment", {
+    component_: rock, offer_address_: offerAddress.Ok, host_id_: alice.agentId, nonce_: nonceString,
+    timelock_: { modulus: testModulus, totient: testTotient, base: "2", squarings: 1000 }
+  })
+  const moveAddress = await alice.callSync("roshambo", "new_move", {
+    component_: paper, commitment_address_: commitmentAddress.Ok, challenger_id_: bob.agentId
+  })
+  await new Promise(resolve => setTimeout(resolve, 2000))
+  const gameResultAddress = await charlie.callSync("roshambo", "new_unsealed_game_result", { move_address: moveAddress.Ok })
+  t.deepEqual(Object.keys(gameResultAddress)[0], "Ok")
+
+  const gameResult = await charlie.callSync("roshambo", "get_game_result", { address: gameResultAddress.Ok })
+  t.deepEqual(gameResult.Ok.Win.reveal, { component: rock, nonce: nonceString })
+})
+
+scenario.runTape("Time-lock puzzles must be solvable and unfactorable", async (t, { alice, bob }) => {
+  const offerAddress = await alice.callSync("roshambo", "new_offer", {
+    challenger_id_: bob.agentId, format_id_: "format", reveal_deadline_: 1, protocol_: "Asymmetric"
+  })
+  const commitmentData = { component_: rock, offer_address_: offerAddress.Ok, host_id_: alice.agentId, nonce_: nonceString }
+
+  // toy primes 1009 and 1013
+  const smallModulus = await bob.callSync("roshambo", "new_commitment", {
+    ...commitmentData, timelock_: { modulus: "1022117", totient: "1020096", base: "2", squarings: 1000 }
+  })
+  t.deepEqual(Object.keys(smallModulus)[0], "SerializationError")
+
+  // more squarings than anyone can do before the one second deadline
+  const tooSlow = await bob.callSync("roshambo", "new_commitment", {
+    ...commitmentData, timelock_: { modulus: testModulus, totient: testTotient, base: "2", squarings: 1000000000 }
+  })
+  t.deepEqual(Object.keys(tooSlow)[0], "SerializationError")
+})
+
+scenario.runTape("Weak and reused nonces", async (t, { alice, bob }) => {
+  const offerAddress = await alice.callSync("roshambo", "new_offer", {
+    challenger_id_: bob.agentId, format_id_: "format", reveal_deadline_: 3600, protocol_: "Asymmetric"
+  })
+  const commitmentData = { component_: rock, offer_address_: offerAddress.Ok, host_id_: alice.agentId }
+
+  const weak = await bob.callSync("roshambo", "new_commitment", { ...commitmentData, nonce_: "random" })
+  t.deepEqual(Object.keys(weak)[0], "Err")
+
+  const first = await bob.callSync("roshambo", "new_commitment", { ...commitmentData, nonce_: nonceString })
+  t.deepEqual(Object.keys(first)[0], "Ok")
+  const reused = await bob.callSync("roshambo", "new_commitment", { ...commitmentData, nonce_: nonceString })
+  t.deepEqual(Object.keys(reused)[0], "Err")
+})
+
+scenario.runTape("Zome-derived nonce", async (t, { alice, bob }) => {
+  const offerAddress = await alice.callSync("roshambo", "new_offer", {
+    challenger_id_: bob.agentId, format_id_: "format", reveal_deadline_: 3600, protocol_: "Asymmetric"
+  })
+  const commitmentAddress = await bob.callSync("roshambo", "new_commitment", {
+    component_: rock, offer_address_: offerAddress.Ok, host_id_: alice.agentId
+  })
+  const moveAddress = await alice.callSync("roshambo", "new_move", {
+    component_: paper, commitment_address_: commitmentAddress.Ok, challenger_id_: bob.agentId
+  })
+  const gameResultAddress = await bob.callSync("roshambo", "new_game_result", { move_address: moveAddress.Ok })
+  t.deepEqual(Object.keys(gameResultAddress)[0], "Ok")
+})
+
+scenario.runTape("Commitment with a recorded hash algorithm", async (t, { alice, bob }) => {
+  const offerAddress = await alice.callSync("roshambo", "new_offer", {
+    challenger_id_: bob.agentId, format_id_: "format", reveal_deadline_: 3600, protocol_: "Asymmetric"
+  })
+  const commitmentAddress = await bob.callSync("roshambo", "new_commitment", {
+    component_: rock, offer_address_: offerAddress.Ok, host_id_: alice.agentId, nonce_: nonceString, hash_algorithm_: "Blake2b256"
+  })
+  const commitment = await alice.callSync("roshambo", "get_commitment", { address: commitmentAddress.Ok })
+  t.deepEqual(commitment.Ok.hash_algorithm, "Blake2b256")
+
+  const moveAddress = await alice.callSync("roshambo", "new_move", {
+    component_: paper, commitment_address_: commitmentAddress.Ok, challenger_id_: bob.agentId
+  })
+  const gameResultAddress = await bob.callSync("roshambo", "new_game_result", { move_address: moveAddress.Ok })
+  t.deepEqual(Object.keys(gameResultAddress)[0], "Ok")
+})
+
+scenario.runTape("Commitment hash test vectors", async (t, { alice }) => {
+  const vectors = require("./commitment-vectors.json")
+  for (const vector of vectors) {
+    for (const hashAlgorithm of Object.keys(vector.hashes)) {
+      const hash = await alice.callSync("roshambo", "compute_commitment_hash", {
+        reveal: vector.reveal, offer_address: vector.offer_address, challenger_id: vector.challenger_id, hash_algorithm: hashAlgorithm
+      })
+      t.deepEqual(hash.Ok, vector.hashes[hashAlgorithm])
+    }
+  }
+})
+
+scenario.runTape("Host confirms a game result", async (t, { alice, bob }) => {
+  const moveAddress = await playUntilReveal({ alice, bob }, 3600)
+  const gameResultAddress = await bob.callSync("roshambo", "new_game_result", { move_address: moveAddress })
+
+  const reported = await alice.callSync("roshambo", "get_result_status", { move_address: moveAddress })
+  t.deepEqual(reported.Ok, "Reported")
+
+  const confirmationAddress = await alice.callSync("roshambo", "new_result_confirmation", { game_result_address: gameResultAddress.Ok })
+  t.deepEqual(Object.keys(confirmationAddress)[0], "Ok")
+  const confirmed = await alice.callSync("roshambo", "get_result_status", { move_address: moveAddress })
+  t.deepEqual(confirmed.Ok, "Confirmed")
+})
+
+scenario.runTape("Disputes re-run the checks", async (t, { alice, bob, charlie }) => {
+  const moveAddress = await playUntilReveal({ alice, bob }, 3600)
+  const gameResultAddress = await bob.callSync("roshambo", "new_game_result", { move_address: moveAddress })
+
+  const disputeAddress = await alice.callSync("roshambo", "new_dispute", { subject_address: gameResultAddress.Ok, reason: "WrongOutcome", referenced_addresses: [moveAddress] })
+  const dispute = await alice.callSync("roshambo", "get_dispute", { address: disputeAddress.Ok })
+  t.deepEqual(dispute.Ok.verdict, "Rejected")
+
+  const timeoutAddress = await alice.callSync("roshambo", "new_dispute", { subject_address: moveAddress, reason: "Timeout", referenced_addresses: [] })
+  const timeout = await alice.callSync("roshambo", "get_dispute", { address: timeoutAddress.Ok })
+  t.deepEqual(timeout.Ok.verdict, "Inconclusive")
+
+  const disputes = await bob.callSync("roshambo", "get_disputes", { address: gameResultAddress.Ok })
+  t.deepEqual(disputes.Ok, [disputeAddress.Ok])
+  const disputed = await bob.callSync("roshambo", "is_game_disputed", { move_address: moveAddress })
+  t.deepEqual(disputed.Ok, false)
+
+  const outsiderAddress = await charlie.callSync("roshambo", "new_dispute", { subject_address: gameResultAddress.Ok, reason: "WrongOutcome", referenced_addresses: [] })
+  t.deepEqual(Object.keys(outsiderAddress)[0], "SerializationError")
+})
+
+scenario.runTape("Jury overturns a disputed forfeit claim", async (t, { alice, bob, charlie }) => {
+  // charlie joins the player index, so is the only agent who can serve as a juror
+  await charlie.callSync("roshambo", "new_offer", {
+    challenger_id_: alice.agentId, format_id_: "format", reveal_deadline_: 3600, protocol_: "Asymmetric"
+  })
+  const moveAddress = await playUntilReveal({ alice, bob }, 0)
+  const claimAddress = await alice.callSync("roshambo", "new_forfeit_claim", { move_address: moveAddress })
+  const disputeAddress = await bob.callSync("roshambo", "new_dispute", { subject_address: claimAddress.Ok, reason: "Timeout", referenced_addresses: [] })
+
+  const juryAddress = await bob.callSync("roshambo", "new_jury", { dispute_address: disputeAddress.Ok })
+  const jury = await alice.callSync("roshambo", "get_jury", { address: juryAddress.Ok })
+  t.deepEqual(jury.Ok.jurors, [charlie.agentId])
+
+  const outsiderVote = await alice.callSync("roshambo", "new_vote_commitment", { jury_address: juryAddress.Ok, vote: "Reject" })
+  t.deepEqual(Object.keys(outsiderVote)[0], "SerializationError")
+
+  const voteCommitmentAddress = await charlie.callSync("roshambo", "new_vote_commitment", { jury_address: juryAddress.Ok, vote: "Uphold" })
+  const wrongReveal = await charlie.callSync("roshambo", "new_vote_reveal", { vote_commitment_address: voteCommitmentAddress.Ok, vote: "Reject" })
+  t.deepEqual(Object.keys(wrongReveal)[0], "SerializationError")
+  await charlie.callSync("roshambo", "new_vote_reveal", { vote_commitment_address: voteCommitmentAddress.Ok, vote: "Uphold" })
+
+  const juryVerdictAddress = await bob.callSync("roshambo", "new_jury_verdict", { dispute_address: disputeAddress.Ok })
+  t.deepEqual(Object.keys(juryVerdictAddress)[0], "Ok")
+  const verdict = await alice.callSync("roshambo", "get_dispute_verdict", { dispute_address: disputeAddress.Ok })
+  t.ok(verdict.Ok.Upheld)
+
+  const outcome = await alice.callSync("roshambo", "get_outcome", { move_address: moveAddress })
+  t.deepEqual(outcome.Ok, null)
+  const disputed = await alice.callSync("roshambo", "is_game_disputed", { move_address: moveAddress })
+  t.deepEqual(disputed.Ok, true)
+})
+
+scenario.runTape("Elo ratings from outcomes", async (t, { alice, bob, charlie }) => {
+  const moveAddress = await playUntilReveal({ alice, bob }, 3600)
+  await bob.callSync("roshambo", "new_game_result", { move_address: moveAddress })
+
+  const aliceRating = await charlie.callSync("roshambo", "get_rating", { agent_id: alice.agentId, format_id: "format" })
+  const bobRating = await charlie.callSync("roshambo", "get_rating", { agent_id: bob.agentId, format_id: "format" })
+  t.deepEqual(aliceRating.Ok, { rating: 1532, deviation: 0, games: 1, provisional: true })
+  t.deepEqual(bobRating.Ok, { rating: 1468, deviation: 0, games: 1, provisional: true })
+
+  const history = await charlie.callSync("roshambo", "get_rating_history", { agent_id: bob.agentId, format_id: "format" })
+  t.deepEqual(history.Ok.length, 1)
+  t.deepEqual(history.Ok[0].opponent_ids, [alice.agentId])
+  t.deepEqual(history.Ok[0].change, -32)
+
+  const unrated = await alice.callSync("roshambo", "get_rating", { agent_id: charlie.agentId, format_id: "format" })
+  t.deepEqual(unrated.Ok, { rating: 1500, deviation: 0, games: 0, provisional: true })
+})
+
+scenario.runTape("Format leaderboard", async (t, { alice, bob, charlie }) => {
+  const moveAddress = await playUntilReveal({ alice, bob }, 3600)
+  await bob.callSync("roshambo", "new_game_result", { move_address: moveAddress })
+
+  const leaderboard = await charlie.callSync("roshambo", "get_leaderboard", { format_id: "format", page: 0 })
+  t.deepEqual(leaderboard.Ok.map((entry) => entry.agent_id), [alice.agentId, bob.agentId])
+  t.deepEqual(leaderboard.Ok[0].wins, 1)
+  t.deepEqual(leaderboard.Ok[1].losses, 1)
+
+  const established = await charlie.callSync("roshambo", "get_leaderboard", { format_id: "format", page: 0, min_games: 2 })
+  t.deepEqual(established.Ok, [])
+  const secondPage = await charlie.callSync("roshambo", "get_leaderboard", { format_id: "format", page: 1 })
+  t.deepEqual(secondPage.Ok, [])
+})
+
+scenario.runTape("Rating snapshots", async (t, { alice, bob, charlie }) => {
+  const moveAddress = await playUntilReveal({ alice, bob }, 3600)
+  await bob.callSync("roshambo", "new_game_result", { move_address: moveAddress })
+
+  const snapshotAddress = await charlie.callSync("roshambo", "new_rating_snapshot", { format_id: "format" })
+  const latest = await alice.callSync("roshambo", "get_latest_rating_snapshot", { format_id: "format" })
+  t.deepEqual(latest.Ok, snapshotAddress.Ok)
+  const snapshot = await alice.callSync("roshambo", "get_rating_snapshot", { address: snapshotAddress.Ok })
+  t.deepEqual(snapshot.Ok.outcome_count, 1)
+  t.deepEqual(snapshot.Ok.standings.length, 2)
+
+  const emptySnapshot = await charlie.callSync("roshambo", "new_rating_snapshot", { format_id: "format" })
+  t.deepEqual(Object.keys(emptySnapshot)[0], "Err")
+
+  const aliceRating = await bob.callSync("roshambo", "get_rating", { agent_id: alice.agentId, format_id: "format" })
+  t.deepEqual(aliceRating.Ok.rating, 1532)
+})
+
+scenario.runTape("First-to-one match", async (t, { alice, bob, charlie }) => {
+  const matchAddress = await alice.callSync("roshambo", "new_match", {
+    format_id: "format", rule: { FirstTo: 1 }, participants: [alice.agentId, bob.agentId]
+  })
+  const outsiderOffer = await charlie.callSync("roshambo", "new_offer", {
+    challenger_id_: alice.agentId, format_id_: "format", reveal_deadline_: 3600, protocol_: "Asymmetric", match_address_: matchAddress.Ok
+  })
+  t.deepEqual(Object.keys(outsiderOffer)[0], "SerializationError")
+
+  const offerAddress = await alice.callSync("roshambo", "new_offer", {
+    challenger_id_: bob.agentId, format_id_: "format", reveal_deadline_: 3600, protocol_: "Asymmetric", match_address_: matchAddress.Ok
+  })
+  const commitmentAddress = await bob.callSync("roshambo", "new_commitment", {
+    component_: rock, offer_address_: offerAddress.Ok, host_id_: alice.agentId, nonce_: nonceString
+  })
+  const moveAddress = await alice.callSync("roshambo", "new_move", {
+    component_: paper, commitment_address_: commitmentAddress.Ok, challenger_id_: bob.agentId
+  })
+  const earlyResult = await alice.callSync("roshambo", "new_match_result", { match_address: matchAddress.Ok })
+  t.deepEqual(Object.keys(earlyResult)[0], "Err")
+
+  await bob.callSync("roshambo", "new_game_result", { move_address: moveAddress.Ok })
+  const matchResultAddress = await alice.callSync("roshambo", "new_match_result", { match_address: matchAddress.Ok })
+  const matchResult = await bob.callSync("roshambo", "get_match_result", { address: matchResultAddress.Ok })
+  t.deepEqual(matchResult.Ok.winner_id, alice.agentId)
+  t.deepEqual(matchResult.Ok.wins, [1, 0])
+  t.deepEqual(matchResult.Ok.round_move_addresses, [moveAddress.Ok])
+})
+
+scenario.runTape("Alternating match replays draws", async (t, { alice, bob }) => {
+  const matchAddress = await alice.callSync("roshambo", "new_match", {
+    format_id: "format", rule: { FirstTo: 1 }, participants: [alice.agentId, bob.agentId], alternate_roles: true, replay_draws: true
+  })
+  const outOfTurnOffer = await bob.callSync("roshambo", "new_offer", {
+    challenger_id_: alice.agentId, format_id_: "format", reveal_deadline_: 3600, protocol_: "Asymmetric", match_address_: matchAddress.Ok
+  })
+  t.deepEqual(Object.keys(outOfTurnOffer)[0], "SerializationError")
+
+  const offerAddress = await alice.callSync("roshambo", "new_offer", {
+    challenger_id_: bob.agentId, format_id_: "format", reveal_deadline_: 3600, protocol_: "Asymmetric", match_address_: matchAddress.Ok
+  })
+  const commitmentAddress = await bob.callSync("roshambo", "new_commitment", {
+    component_: rock, offer_address_: offerAddress.Ok, host_id_: alice.agentId, nonce_: nonceString
+  })
+  const drawnMoveAddress = await alice.callSync("roshambo", "new_move", {
+    component_: rock, commitment_address_: commitmentAddress.Ok, challenger_id_: bob.agentId
+  })
+  await bob.callSync("roshambo", "new_game_result", { move_address: drawnMoveAddress.Ok })
+
+  // bob hosts the second round, so only bob can offer the replay
+  const outOfTurnReplay = await alice.callSync("roshambo", "new_replay_offer", { move_address: drawnMoveAddress.Ok })
+  t.deepEqual(Object.keys(outOfTurnReplay)[0], "SerializationError")
+  const replayOfferAddress = await bob.callSync("roshambo", "new_replay_offer", { move_address: drawnMoveAddress.Ok })
+  const rounds = await alice.callSync("roshambo", "get_match_rounds", { match_address: matchAddress.Ok })
+  t.deepEqual(rounds.Ok, [offerAddress.Ok, replayOfferAddress.Ok])
+  const replayCommitmentAddress = await alice.callSync("roshambo", "new_commitment", {
+    component_: rock, offer_address_: replayOfferAddress.Ok, host_id_: bob.agentId, nonce_: nonceString
+  })
+  const replayMoveAddress = await bob.callSync("roshambo", "new_move", {
+    component_: paper, commitment_address_: replayCommitmentAddress.Ok, challenger_id_: alice.agentId
+  })
+  await alice.callSync("roshambo", "new_game_result", { move_address: replayMoveAddress.Ok })
+
+  const matchResultAddress = await bob.callSync("roshambo", "new_match_result", { match_address: matchAddress.Ok })
+  const matchResult = await alice.callSync("roshambo", "get_match_result", { address: matchResultAddress.Ok })
+  t.deepEqual(matchResult.Ok.winner_id, bob.agentId)
+  t.deepEqual(matchResult.Ok.wins, [0, 1])
+  t.deepEqual(matchResult.Ok.round_move_addresses, [drawnMoveAddress.Ok, replayMoveAddress.Ok])
+})
+
+scenario.runTape("Match without alternating roles replays draws", async (t, { alice, bob }) => {
+  const matchAddress = await alice.callSync("roshambo", "new_match", {
+    format_id: "format", rule: { FirstTo: 1 }, participants: [alice.agentId, bob.agentId], replay_draws: true
+  })
+  const offerAddress = await alice.callSync("roshambo", "new_offer", {
+    challenger_id_: bob.agentId, format_id_: "format", reveal_deadline_: 3600, protocol_: "Asymmetric", match_address_: matchAddress.Ok
+  })
+  const commitmentAddress = await bob.callSync("roshambo", "new_commitment", {
+    component_: rock, offer_address_: offerAddress.Ok, host_id_: alice.agentId, nonce_: nonceString
+  })
+  const drawnMoveAddress = await alice.callSync("roshambo", "new_move", {
+    component_: rock, commitment_address_: commitmentAddress.Ok, challenger_id_: bob.agentId
+  })
+  const earlyReplay = await bob.callSync("roshambo", "new_replay_offer", { move_address: drawnMoveAddress.Ok })
+  t.deepEqual(Object.keys(earlyReplay)[0], "Err")
+  const drawResultAddress = await bob.callSync("roshambo", "new_game_result", { move_address: drawnMoveAddress.Ok })
+
+  // either player may offer the replay and host it
+  const replayOfferAddress = await bob.callSync("roshambo", "new_replay_offer", { move_address: drawnMoveAddress.Ok })
+  const rounds = await alice.callSync("roshambo", "get_match_rounds", { match_address: matchAddress.Ok })
+  t.deepEqual(rounds.Ok, [offerAddress.Ok, replayOfferAddress.Ok])
+  const replayOffer = await alice.callSync("roshambo", "get_offer", { address: replayOfferAddress.Ok })
+  t.deepEqual(replayOffer.Ok.challenger_id, alice.agentId)
+  t.deepEqual(replayOffer.Ok.round, 2)
+  t.deepEqual(replayOffer.Ok.previous_result_address, drawResultAddress.Ok)
+})
+
+// offers the next round of the match, where bob's scissors challenge alice, with a nonce the zome derives
+const offerMatchRound = async ({ alice, bob }, matchAddress) => {
+  const offerAddress = await alice.callSync("roshambo", "new_offer", {
+    challenger_id_: bob.agentId, format_id_: "format", reveal_deadline_: 3600, protocol_: "Asymmetric", match_address_: matchAddress
+  })
+  const commitmentAddress = await bob.callSync("roshambo", "new_commitment", {
+    component_: scissors, offer_address_: offerAddress.Ok, host_id_: alice.agentId
+  })
+  return commitmentAddress.Ok
+}
+
+const playMatchRound = async ({ alice, bob }, commitmentAddress, hostComponent) => alice.callSync("roshambo", "new_move", {
+  component_: hostComponent, commitment_address_: commitmentAddress, challenger_id_: bob.agentId
+})
+
+scenario.runTape("Deck match limits component use", async (t, { alice, bob }) => {
+  const matchAddress = await alice.callSync("roshambo", "new_match", {
+    format_id: "format", rule: { FirstTo: 2 }, participants: [alice.agentId, bob.agentId], component_limit: 1
+  })
+  const moveAddress = await playMatchRound({ alice, bob }, await offerMatchRound({ alice, bob }, matchAddress.Ok), rock)
+  await bob.callSync("roshambo", "new_game_result", { move_address: moveAddress.Ok })
+
+  const commitmentAddress = await offerMatchRound({ alice, bob }, matchAddress.Ok)
+  const repeatedMove = await playMatchRound({ alice, bob }, commitmentAddress, rock)
+  t.ok(JSON.stringify(repeatedMove.SerializationError).includes("Rock has already been played 1 times"))
+  const freshMove = await playMatchRound({ alice, bob }, commitmentAddress, paper)
+  t.deepEqual(Object.keys(freshMove)[0], "Ok")
+
+  // bob's scissors were already used up in the first round
+  const repeatedReveal = await bob.callSync("roshambo", "new_game_result", { move_address: freshMove.Ok })
+  t.deepEqual(Object.keys(repeatedReveal)[0], "SerializationError")
+})
+
+scenario.runTape("Match move constraints", async (t, { alice, bob }) => {
+  const matchAddress = await alice.callSync("roshambo", "new_match", {
+    format_id: "format", rule: { BestOf: 3 }, participants: [alice.agentId, bob.agentId], constraints: [{ MaxConsecutive: 1 }]
+  })
+  const moveAddress = await playMatchRound({ alice, bob }, await offerMatchRound({ alice, bob }, matchAddress.Ok), rock)
+  await bob.callSync("roshambo", "new_game_result", { move_address: moveAddress.Ok })
+
+  const commitmentAddress = await offerMatchRound({ alice, bob }, matchAddress.Ok)
+  const repeatedMove = await playMatchRound({ alice, bob }, commitmentAddress, rock)
+  t.ok(JSON.stringify(repeatedMove.SerializationError).includes("Rock cannot be played more than 1 rounds in a row"))
+  const freshMove = await playMatchRound({ alice, bob }, commitmentAddress, paper)
+  t.deepEqual(Object.keys(freshMove)[0], "Ok")
+})
+
+scenario.runTape("Match commitment covers every round", async (t, { alice, bob }) => {
+  const matchAddress = await alice.callSync("roshambo", "new_match", {
+    format_id: "format", rule: { BestOf: 3 }, participants: [alice.agentId, bob.agentId]
+  })
+  const matchCommitmentAddress = await bob.callSync("roshambo", "new_match_commitment", {
+    match_address: matchAddress.Ok, components: [rock, scissors, rock]
+  })
+  t.deepEqual(Object.keys(matchCommitmentAddress)[0], "Ok")
+  const secondCommitment = await bob.callSync("roshambo", "new_match_commitment", {
+    match_address: matchAddress.Ok, components: [paper, paper, paper]
+  })
+  t.deepEqual(Object.keys(secondCommitment)[0], "SerializationError")
+
+  const offerAddress = await alice.callSync("roshambo", "new_offer", {
+    challenger_id_: bob.agentId, format_id_: "format", reveal_deadline_: 3600, protocol_: "Asymmetric", match_address_: matchAddress.Ok
+  })
+  const moveAddress = await alice.callSync("roshambo", "new_round_move", { component: paper, offer_address: offerAddress.Ok })
+  const roundRevealAddress = await bob.callSync("roshambo", "new_round_reveal", { move_address: moveAddress.Ok })
+  const roundReveal = await alice.callSync("roshambo", "get_round_reveal", { address: roundRevealAddress.Ok })
+  t.deepEqual(roundReveal.Ok.round, 1)
+  t.deepEqual(roundReveal.Ok.reveal.component, rock)
+  t.deepEqual(roundReveal.Ok.proof.length, 2)
+
+  await bob.callSync("roshambo", "new_game_result", { move_address: moveAddress.Ok })
+  const outcome = await alice.callSync("roshambo", "get_outcome", { move_address: moveAddress.Ok })
+  t.deepEqual(outcome.Ok.Win.winner_id, alice.agentId)
+})
+
+scenario.runTape("Game set in one commitment", async (t, { alice, bob }) => {
+  const offerAddress = await alice.callSync("roshambo", "new_offer", {
+    challenger_id_: bob.agentId, format_id_: "format", reveal_deadline_: 3600, protocol_: "Asymmetric", games_: 3
+  })
+  const singleCommitment = await bob.callSync("roshambo", "new_commitment", {
+    component_: rock, offer_address_: offerAddress.Ok, host_id_: alice.agentId, nonce_: nonceString
+  })
+  t.deepEqual(Object.keys(singleCommitment)[0], "SerializationError")
+  const shortCommitment = await bob.callSync("roshambo", "new_set_commitment", {
+    components: [rock, paper], offer_address: offerAddress.Ok
+  })
+  t.deepEqual(Object.keys(shortCommitment)[0], "SerializationError")
+
+  const commitmentAddress = await bob.callSync("roshambo", "new_set_commitment", {
+    components: [rock, paper, scissors], offer_address: offerAddress.Ok
+  })
+  const moveAddress = await alice.callSync("roshambo", "new_set_move", {
+    components: [paper, paper, paper], commitment_address: commitmentAddress.Ok
+  })
+  const setResultAddress = await bob.callSync("roshambo", "new_set_result", { move_address: moveAddress.Ok })
+  const setResult = await alice.callSync("roshambo", "get_set_result", { address: setResultAddress.Ok })
+  t.deepEqual(setResult.Ok.winners, [alice.agentId, null, bob.agentId])
+})
+
+scenario.runTape("Free-for-all ranking", async (t, { alice, bob, charlie }) => {
+  const gameAddress = await alice.callSync("roshambo", "new_free_for_all", {
+    format_id: "format", players: [alice.agentId, bob.agentId, charlie.agentId]
+  })
+  const aliceCommitment = await alice.callSync("roshambo", "new_free_for_all_commitment", { game_address: gameAddress.Ok, component: rock })
+  const bobCommitment = await bob.callSync("roshambo", "new_free_for_all_commitment", { game_address: gameAddress.Ok, component: scissors })
+  const earlyReveal = await alice.callSync("roshambo", "new_free_for_all_reveal", { commitment_address: aliceCommitment.Ok })
+  t.deepEqual(Object.keys(earlyReveal)[0], "SerializationError")
+
+  const charlieCommitment = await charlie.callSync("roshambo", "new_free_for_all_commitment", { game_address: gameAddress.Ok, component: scissors })
+  await alice.callSync("roshambo", "new_free_for_all_reveal", { commitment_address: aliceCommitment.Ok })
+  await bob.callSync("roshambo", "new_free_for_all_reveal", { commitment_address: bobCommitment.Ok })
+  await charlie.callSync("roshambo", "new_free_for_all_reveal", { commitment_address: charlieCommitment.Ok })
+
+  const rankingAddress = await bob.callSync("roshambo", "new_free_for_all_ranking", { game_address: gameAddress.Ok })
+  const ranking = await charlie.callSync("roshambo", "get_free_for_all_ranking", { address: rankingAddress.Ok })
+  t.deepEqual(ranking.Ok.scores, [2, 0, 0])
+  t.deepEqual(ranking.Ok.ranks, [0, 1, 1])
+})
@@ -0,0 +1,62 @@
+// Canonical byte encodings, so clients in any language can reproduce what the zome hashes and signs.
+// See "Canonical encoding" in the README for the specification and test vectors.
+//
+//   string(s) = u32 big-endian byte length of s in UTF-8 || UTF-8 bytes of s
+//   list(l)   = u32 big-endian element count || string(e) for each element, in order
+//
+// Every encoding starts with string(tag), naming what is encoded and its version.
+// Signatures are made over the lowercase hex of an encoding, since hdk::sign takes a string.
+
+pub struct Encoder {
+    bytes: Vec<u8>,
+}
+
+impl Encoder {
+    pub fn new(tag: &str) -> Encoder {
+        Encoder { bytes: Vec::new() }.string(tag)
+    }
+
+    pub fn string(mut self, value: &str) -> Encoder {
+        self.push_u32(value.len() as u32);
+        self.bytes.extend_from_slice(value.as_bytes());
+        self
+    }
+
+    pub fn list(mut self, values: &[String]) -> Encoder {
+        self.push_u32(values.len() as u32);
+        values.iter().fold(self, |encoder, value| encoder.string(value))
+    }
+
+    // a list of zero or one elements
+    pub fn optional(self, value: Option<String>) -> Encoder {
+        let values: Vec<String> = value.into_iter().collect();
+        self.list(&values)
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    fn push_u32(&mut self, value: u32) {
+        for shift in (0..4).rev() {
+            self.bytes.push((value >> (shift * 8)) as u8);
+        }
+    }
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub fn from_hex(hex: &str) -> Result<Vec<u8>, String> {
+    if hex.len() % 2 != 0 {
+        return Err(String::from("Odd length hex string"));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| match hex.get(index..index + 2).and_then(|pair| u8::from_str_radix(pair, 16).ok()) {
+            Some(byte) => Ok(byte),
+            None => Err(format!("Not a hex string: {}", hex)),
+        })
+        .collect()
+}
@@ -4,7 +4,10 @@
 extern crate hdk;
 extern crate serde;
 extern crate multihash;
-// extern crate rand;
+extern crate blake2;
+extern crate rust_base58;
+extern crate num_bigint;
+extern crate num_traits;
 
 #[macro_use]
 extern crate serde_derive;
@@ -13,11 +16,20 @@ extern crate serde_json;
 #[macro_use]
 extern crate holochain_core_types_derive;
 
-use multihash::Hash as Multihash;
-// use rand::{thread_rng, Rng};
-// use rand::distributions::Alphanumeric;
+mod canonical;
+mod merkle;
+mod rating;
+mod timelock;
+
+use multihash::{encode, Hash as Multihash};
+use blake2::VarBlake2b;
+use blake2::digest::{Input, VariableOutput};
+use rust_base58::ToBase58;
+use std::collections::BTreeMap;
 use std::convert::TryInto;
 
+use rating::{PlayerRating, RatingPolicy, RatingSystem};
+
 use hdk::{
     entry_definition::ValidatingEntryType,
     error::ZomeApiResult,
@@ -32,6 +44,8 @@ use hdk::holochain_core_types::{
     // agent::AgentId,
     hash::HashString,
     chain_header::ChainHeader,
+    signature::{Provenance, Signature},
+    time::Timeout,
 };
 
 use holochain_wasm_utils::api_serialization::get_entry::{
@@ -39,7 +53,18 @@ use holochain_wasm_utils::api_serialization::get_entry::{
     GetEntryOptions,
 };
 
-// TODO assume clients will input nonces
+// clients either input nonces, or leave them to the zome to derive from the agent's signature, and
+// their reveals are kept as private reveal_secret entries on the committing agent's chain,
+// so clients do not have to remember the component and nonce themselves
+
+// nonces guard the committed component against brute force, so they must be long enough and never reused
+const MIN_NONCE_LENGTH: usize = 16;
+// disputes the checks cannot settle go to a jury drawn from the player index
+const JURY_SIZE: usize = 5;
+// agents on each page of a format's leaderboard
+const LEADERBOARD_PAGE_SIZE: usize = 20;
+// outcomes are indexed by the day they were committed, so ratings only read the days since the latest snapshot
+const SECONDS_PER_DAY: u64 = 86400;
 
 
 // each entry should be a state machine in the game
@@ -49,6 +74,21 @@ use holochain_wasm_utils::api_serialization::get_entry::{
     // 2. opponent commits a move hash (implicitly confirms the game is happening)
     // 3. player commits a move
     // 4. opponent commits a game result
+    // 5. if the opponent has not committed a game result by the offer's reveal deadline,
+    //    the player may commit a forfeit claim instead, which counts as a win for the player
+// symmetric protocol:
+    // 1. identify an opponent (entry w/ address)
+    // 2. opponent commits a move hash
+    // 3. player commits a move hash (sealed move)
+    // 4. both reveal their moves, in any order
+    // 5. either commits a game result
+    // 6. after the reveal deadline, a player who has revealed may claim a forfeit from one who has not
+// arbitrated offers:
+    // the challenger escrows their reveal with the offer's arbiter when committing, and instead of
+    // claiming forfeits, the arbiter commits the game result if the challenger misses the reveal deadline
+// time-locked commitments:
+    // the challenger also seals their reveal in a time-lock puzzle, so after the reveal deadline
+    // any agent can solve the puzzle and commit the game result
 
 // Questions
     // How do we get AgentIds? Is it the result if you get what's at the agent's address?
@@ -79,18 +119,88 @@ impl PartialEq for Player {
 }
 */
 
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+pub enum Protocol {
+    Asymmetric, // challenger commits a hash, host plays in the clear
+    Symmetric,  // both commit hashes, then both reveal
+}
+
 #[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
 pub struct Offer {
     challenger_id: Address,
     format_id: String,
+    reveal_deadline: u64,                     // seconds after the move header timestamp
+    protocol: Protocol,
+    arbiter_id: Option<Address>,
+    match_address: Option<Address>,           // set when the offer is a round of a match
+    round: Option<u64>,                       // the round's number in its match, counted from 1
+    previous_result_address: Option<Address>, // a result of the round before, set on every round after the first
+    games: Option<u64>,                       // set when the offer covers a set of games, played in bulk
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+pub enum HashAlgorithm {
+    Sha2256,
+    Sha3256,
+    Blake2b256,
+}
+
+impl HashAlgorithm {
+    fn name(&self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha2256 => "Sha2256",
+            HashAlgorithm::Sha3256 => "Sha3256",
+            HashAlgorithm::Blake2b256 => "Blake2b256",
+        }
+    }
+}
+
+// DNA property "hash_algorithms" restricts the algorithms new commitments may use,
+// e.g. {"allowed": ["Sha3256", "Blake2b256"], "formats": {"format": ["Sha3256"]}}
+// Old commitments always validate with the algorithm they recorded.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct HashAlgorithmPolicy {
+    allowed: Option<Vec<HashAlgorithm>>,
+    formats: Option<BTreeMap<String, Vec<HashAlgorithm>>>,
+}
+
+// DNA property "timelocks" bounds time-lock puzzles so they stay solvable and unfactorable,
+// e.g. {"squarings_per_second": 100000, "min_modulus_bits": 2048}
+// A puzzle may take at most reveal_deadline seconds of squarings to solve.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct TimelockPolicy {
+    squarings_per_second: Option<u64>,
+    min_modulus_bits: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
 pub struct Commitment {
     hash: HashString,
+    hash_algorithm: HashAlgorithm, // also used for the host's hash in the symmetric protocol
     offer_address: Address,
     host_id: Address,
     format_id: String,
+    timelock: Option<TimelockPuzzle>,
+    nonce_counter: Option<u64>, // set when the zome derived the nonce
+    signature: String,          // author's signature over commitment_content
+}
+
+// The challenger's reveal sealed with the key base^(2^squarings) mod modulus
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct TimelockPuzzle {
+    modulus: String,       // decimal
+    base: String,          // decimal
+    squarings: u64,
+    sealed_reveal: String, // hex
+}
+
+// What the challenger needs to seal their reveal quickly. The totient is the puzzle's trapdoor and is never stored.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct TimelockSetup {
+    modulus: String,
+    totient: String,
+    base: String,
+    squarings: u64,
 }
 
 #[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
@@ -116,10 +226,23 @@ pub struct Format {
 #[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
 pub struct Move {
     component: Component,
+    commitment_address: Address,    // a match commitment when offer_address is set
+    challenger_id: Address,
+    hash: HashString,
+    format_id: String,
+    offer_address: Option<Address>, // set when the move answers a round of the challenger's match commitment
+    signature: String, // author's signature over move_content
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct SealedMove {
+    host_hash: HashString,
     commitment_address: Address,
     challenger_id: Address,
     hash: HashString,
     format_id: String,
+    nonce_counter: Option<u64>, // set when the zome derived the nonce
+    signature: String,          // author's signature over sealed_move_content
 }
 
 #[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
@@ -135,6 +258,40 @@ impl PartialEq for Reveal {
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct MoveReveal {
+    reveal: Reveal,
+    move_address: Address,
+}
+
+// Kept privately on the chain of whoever committed the hash (a commitment, or a sealed move in the symmetric protocol)
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct RevealSecret {
+    reveal: Reveal,
+    commitment_address: Address,
+}
+
+// Kept privately on the agent's chain: the counter for the next nonce the zome derives for them
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct NonceCounter {
+    next: u64,
+}
+
+// Sent by the challenger to the arbiter over node-to-node messaging
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct EscrowMessage {
+    reveal: Reveal,
+    commitment_address: Address,
+}
+
+// Kept privately on the arbiter's chain
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct EscrowedReveal {
+    reveal: Reveal,
+    commitment_address: Address,
+    challenger_id: Address,
+}
+
 #[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
 pub enum GameResult {
     Win {
@@ -143,12 +300,16 @@ pub enum GameResult {
         winner_id: Address,
         loser_id: Address,
         format_id: String,
+        host_reveal: Option<Reveal>, // symmetric protocol only
+        puzzle_solution: Option<String>, // time-lock key, when the result was opened by solving the puzzle
     },
     Draw {
         reveal: Reveal,
         move_address: Address,
         players: Vec<Address>,
         format_id: String,
+        host_reveal: Option<Reveal>, // symmetric protocol only
+        puzzle_solution: Option<String>, // time-lock key, when the result was opened by solving the puzzle
     },
 }
 
@@ -161,6 +322,8 @@ impl PartialEq for GameResult {
                 winner_id,
                 loser_id,
                 format_id,
+                host_reveal,
+                puzzle_solution,
             } => {
                 match other {
                     GameResult::Win {
@@ -169,12 +332,16 @@ impl PartialEq for GameResult {
                         winner_id: other_winner_id,
                         loser_id: other_loser_id,
                         format_id: other_format_id,
+                        host_reveal: other_host_reveal,
+                        puzzle_solution: other_puzzle_solution,
                     } => {
                         reveal == other_reveal &&
                         move_address == other_move_address &&
                         winner_id == other_winner_id && 
                         loser_id == other_loser_id &&
-                        format_id == other_format_id
+                        format_id == other_format_id &&
+                        host_reveal == other_host_reveal &&
+                        puzzle_solution == other_puzzle_solution
                     },
                     _ => false,
                 }
@@ -184,6 +351,8 @@ impl PartialEq for GameResult {
                 move_address,
                 players,
                 format_id,
+                host_reveal,
+                puzzle_solution,
             } => {
                 match other {
                     GameResult::Draw {
@@ -191,11 +360,15 @@ impl PartialEq for GameResult {
                         move_address: other_move_address,
                         players: other_players,
                         format_id: other_format_id,
+                        host_reveal: other_host_reveal,
+                        puzzle_solution: other_puzzle_solution,
                     } => {
                         reveal == other_reveal &&
                         move_address == other_move_address &&
                         players == other_players &&
-                        format_id == other_format_id
+                        format_id == other_format_id &&
+                        host_reveal == other_host_reveal &&
+                        puzzle_solution == other_puzzle_solution
                     },
                     _ => false,
                 }
@@ -204,6 +377,380 @@ impl PartialEq for GameResult {
     }
 }
 
+impl GameResult {
+    fn move_address(&self) -> &Address {
+        match self {
+            GameResult::Win { move_address, .. } => move_address,
+            GameResult::Draw { move_address, .. } => move_address,
+        }
+    }
+
+    // the challenger's reveal
+    fn reveal(&self) -> &Reveal {
+        match self {
+            GameResult::Win { reveal, .. } => reveal,
+            GameResult::Draw { reveal, .. } => reveal,
+        }
+    }
+}
+
+// The host's acknowledgement of a game result (or the challenger's, if the host reported it)
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct ResultConfirmation {
+    game_result_address: Address,
+    signature: String, // author's signature over confirmation_content
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+pub enum ResultStatus {
+    Reported,  // a game result or forfeit claim from one side
+    Confirmed, // a game result the other side has countersigned
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct ForfeitClaim {
+    move_address: Address,
+    winner_id: Address,
+    loser_id: Address,
+    format_id: String,
+}
+
+// What a finished game means for its players, whether it was decided by a game result or a forfeit claim.
+// Anything consuming results (ratings, leaderboards...) should read outcomes rather than game results.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub enum Outcome {
+    Win {
+        move_address: Address,
+        winner_id: Address,
+        loser_id: Address,
+        format_id: String,
+    },
+    Draw {
+        move_address: Address,
+        players: Vec<Address>,
+        format_id: String,
+    },
+}
+
+impl From<GameResult> for Outcome {
+    fn from(game_result: GameResult) -> Outcome {
+        match game_result {
+            GameResult::Win { move_address, winner_id, loser_id, format_id, .. } => {
+                Outcome::Win { move_address, winner_id, loser_id, format_id }
+            },
+            GameResult::Draw { move_address, players, format_id, .. } => {
+                Outcome::Draw { move_address, players, format_id }
+            },
+        }
+    }
+}
+
+impl Outcome {
+    fn move_address(&self) -> &Address {
+        match self {
+            Outcome::Win { move_address, .. } => move_address,
+            Outcome::Draw { move_address, .. } => move_address,
+        }
+    }
+
+    fn format_id(&self) -> &str {
+        match self {
+            Outcome::Win { format_id, .. } => format_id,
+            Outcome::Draw { format_id, .. } => format_id,
+        }
+    }
+}
+
+impl From<ForfeitClaim> for Outcome {
+    fn from(claim: ForfeitClaim) -> Outcome {
+        Outcome::Win {
+            move_address: claim.move_address,
+            winner_id: claim.winner_id,
+            loser_id: claim.loser_id,
+            format_id: claim.format_id,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+pub enum DisputeReason {
+    InvalidHash,   // a reveal or host reveal does not match its commitment
+    WrongPlayer,   // an entry was authored by, or names, the wrong agent
+    WrongOutcome,  // a game result does not follow from the reveals
+    Timeout,       // a deadline claim, which depends on the players' clocks
+    Other,
+}
+
+// Re-running the subject's checks decides a dispute: a failing check upholds it with the check's error as the finding
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+pub enum Verdict {
+    Upheld { finding: String },
+    Rejected,
+    Inconclusive, // the checks cannot settle it, e.g. timeouts, so it can go to a jury
+}
+
+// A player's challenge of a commitment, move, game result or forfeit claim of their game
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct Dispute {
+    subject_address: Address,
+    reason: DisputeReason,
+    referenced_addresses: Vec<Address>, // any other entries the disputing player points to
+    verdict: Verdict,
+    juror_pool: Vec<Address>, // the player index when the dispute was raised, without the players, sorted
+}
+
+// An agent's standing in a format, replayed from every undisputed outcome by the format's rating system
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct Rating {
+    rating: f64,
+    deviation: f64, // uncertainty of the rating, 0 in Elo
+    games: u64,
+    provisional: bool,
+}
+
+// How one rated game changed an agent's rating
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct RatingChange {
+    move_address: Address,
+    opponent_ids: Vec<Address>,
+    score: f64,  // share of opponents beaten, with draws counting half
+    rating: f64, // after the game
+    deviation: f64,
+    change: f64,
+    timestamp: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+pub enum MatchRule {
+    BestOf(u64),  // decided once a player wins more than half of n rounds, or after n rounds
+    FirstTo(u64), // decided once a player wins k rounds
+}
+
+// A rule on the sequence of components each player plays over a match
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+pub enum MoveConstraint {
+    MaxConsecutive(u64),              // no component more than n rounds in a row
+    PlayAllBeforeRepeat(Vec<String>), // every listed component once before any of them repeats
+}
+
+impl MoveConstraint {
+    // whether a player who played history, in round order, may play component next
+    fn check(&self, history: &[Component], component: &Component) -> Result<(), String> {
+        match self {
+            MoveConstraint::MaxConsecutive(max_rounds) => {
+                let streak: u64 = history.iter().rev().take_while(|earlier| earlier.name == component.name).count() as u64;
+                if streak >= *max_rounds {
+                    return Err(format!("{} cannot be played more than {} rounds in a row", component.name, max_rounds));
+                }
+            },
+            MoveConstraint::PlayAllBeforeRepeat(names) => {
+                let mut played: Vec<String> = Vec::new();
+                for earlier in history {
+                    if names.contains(&earlier.name) && !played.contains(&earlier.name) {
+                        played.push(earlier.name.clone());
+                    }
+                    if played.len() == names.len() {
+                        played.clear();
+                    }
+                }
+                if played.contains(&component.name) {
+                    let missing: Vec<String> = names.iter().filter(|name| !played.contains(*name)).cloned().collect();
+                    return Err(format!("{} cannot be repeated before {} have been played", component.name, missing.join(", ")));
+                }
+            },
+        }
+        Ok(())
+    }
+}
+
+// A series of rounds between the same players, each round a game on an offer with this match_address
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct Match {
+    format_id: String,
+    rule: MatchRule,
+    participants: Vec<Address>, // the first participant hosts the first round
+    alternate_roles: bool,      // host and challenger swap every round, evening out the asymmetric protocol
+    replay_draws: bool,         // drawn rounds do not count, and the next host offers a replay
+    component_limit: Option<u64>, // deck match: each player may play each component at most this many times
+    constraints: Vec<MoveConstraint>,
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+pub struct MatchResult {
+    match_address: Address,
+    round_move_addresses: Vec<Address>, // rounds up to the one that decided the match, in order
+    wins: Vec<u64>,                     // per participant, in the order of the match's participants
+    winner_id: Option<Address>,         // None for a drawn best-of-n
+}
+
+// A challenger's moves for every round of a match at once: the root of a Merkle tree whose
+// leaves are round commitment hashes, so the DHT holds one commitment instead of one per round
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct MatchCommitment {
+    match_address: Address,
+    root: HashString,
+    hash_algorithm: HashAlgorithm,
+    rounds: u64,       // leaves, the first for round 1
+    signature: String, // author's signature over match_commitment_content
+}
+
+// The challenger's reveal for one round of their match commitment
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct RoundReveal {
+    reveal: Reveal,
+    move_address: Address,
+    round: u64,             // counted from 1, in the order of the match's round offers
+    proof: Vec<HashString>, // sibling hashes from the round's leaf up to the root
+}
+
+// The challenger accepts a game set offer with one hash per game
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct SetCommitment {
+    hashes: Vec<HashString>,
+    hash_algorithm: HashAlgorithm,
+    offer_address: Address,
+    host_id: Address,
+    format_id: String,
+    signature: String, // author's signature over set_commitment_content
+}
+
+// The host's components for every game of a set, in the order of the set commitment's hashes
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct SetMove {
+    components: Vec<Component>,
+    commitment_address: Address,
+    challenger_id: Address,
+    format_id: String,
+    signature: String, // author's signature over set_move_content
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+pub struct SetResult {
+    move_address: Address,
+    reveals: Vec<Reveal>,          // the challenger's, one per game
+    winners: Vec<Option<Address>>, // per game, None for a draw
+    format_id: String,
+}
+
+// A game between any number of players: everyone commits, then everyone reveals
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct FreeForAll {
+    format_id: String,
+    players: Vec<Address>,
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct FreeForAllCommitment {
+    game_address: Address,
+    hash: HashString,
+    hash_algorithm: HashAlgorithm,
+    signature: String, // author's signature over free_for_all_commitment_content
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct FreeForAllReveal {
+    commitment_address: Address,
+    reveal: Reveal,
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+pub struct FreeForAllRanking {
+    game_address: Address,
+    reveal_addresses: Vec<Address>, // one per player, in the game's player order
+    scores: Vec<u64>,               // per player, a point for each opponent component theirs beats
+    ranks: Vec<u64>,                // per player, 0 for the best score, equal scores share a rank
+}
+
+// An agent's rating and record in a format, as replayed or as recorded in a snapshot
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Standing {
+    agent_id: Address,
+    rating: f64,
+    deviation: f64,
+    volatility: f64,
+    games: u64,
+    last_played: Option<u64>,
+    wins: u64,
+    draws: u64,
+    losses: u64,
+}
+
+// Standings of a format as of the outcomes covered by this snapshot and the snapshots before it,
+// so readers only need to replay the outcomes that came after
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct RatingSnapshot {
+    format_id: String,
+    previous_snapshot_address: Option<Address>,
+    outcome_addresses: Vec<Address>, // covered since the previous snapshot, in replay order
+    outcomes_hash: HashString,       // chained over every covered outcome, see snapshot_hash
+    outcome_count: u64,              // covered by the whole chain
+    standings: Vec<Standing>,        // sorted by agent
+}
+
+// An agent's line on a format's leaderboard
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct LeaderboardEntry {
+    agent_id: Address,
+    rating: f64,
+    deviation: f64,
+    provisional: bool,
+    games: u64,
+    wins: u64,
+    draws: u64,
+    losses: u64,
+}
+
+// Named entry that agents and outcomes are linked from, e.g. the player index
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct Anchor {
+    name: String,
+}
+
+// Jurors for an inconclusive dispute, drawn deterministically from the dispute's juror pool
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct Jury {
+    dispute_address: Address,
+    jurors: Vec<Address>,
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+pub enum JuryVote {
+    Uphold,
+    Reject,
+}
+
+impl JuryVote {
+    fn name(&self) -> &'static str {
+        match self {
+            JuryVote::Uphold => "Uphold",
+            JuryVote::Reject => "Reject",
+        }
+    }
+}
+
+// A juror's hidden vote, committed like a move: hash of vote_preimage with a derived nonce
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct VoteCommitment {
+    jury_address: Address,
+    hash: HashString,
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct VoteReveal {
+    vote_commitment_address: Address,
+    vote: JuryVote,
+    nonce: String,
+}
+
+// The majority of a jury's revealed votes, which overrides the dispute's own verdict
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+pub struct JuryVerdict {
+    dispute_address: Address,
+    jury_address: Address,
+    verdict: Verdict,
+    vote_reveal_addresses: Vec<Address>,
+}
+
 // Entry definitions
 
 fn define_offer_entry() -> ValidatingEntryType {
@@ -214,7 +761,7 @@ fn define_offer_entry() -> ValidatingEntryType {
         validation_package: || {
             hdk::ValidationPackageDefinition::Entry
         },
-        validation: |_validation_data: hdk::EntryValidationData<Offer>| {
+        validation: |validation_data: hdk::EntryValidationData<Offer>| {
             // challenger_id is an agent
             /*
             match offer.challenger_id {
@@ -222,8 +769,47 @@ fn define_offer_entry() -> ValidatingEntryType {
                 _ => Err(String::from("No challenger").into()),
             }
             */
-            Ok(())
-        }
+            // round offers are between the match's participants, in the roles the match's rules give them,
+            // a game set has at least one game and is a plain asymmetric game in every other way
+            if let hdk::EntryValidationData::Create{entry: offer, validation_data: validation_} = validation_data {
+                validate_offer(offer, &validation_.package.chain_header)
+            } else { Err(String::from("Unreachable").into()) }
+        },
+        links: [
+            to!(
+                "move",
+                tag: "move",
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: |validation_data: hdk::LinkValidationData| {
+                    // the move answers this offer, and is linked by its own author
+                    validate_offer_move_link(validation_data)
+                }
+            ),
+            to!(
+                "sealed_move",
+                tag: "move",
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: |validation_data: hdk::LinkValidationData| {
+                    // the move answers this offer, and is linked by its own author
+                    validate_offer_move_link(validation_data)
+                }
+            ),
+            to!(
+                "set_move",
+                tag: "move",
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: |validation_data: hdk::LinkValidationData| {
+                    // the move answers this offer, and is linked by its own author
+                    validate_offer_move_link(validation_data)
+                }
+            )
+        ]
     )
 }
 
@@ -238,13 +824,8 @@ fn define_commitment_entry() -> ValidatingEntryType {
         validation: |validation_data: hdk::EntryValidationData<Commitment>| {
             // offer.author == commitment.host, commitment.author == offer.challenger
             if let hdk::EntryValidationData::Create{entry: commitment, validation_data: validation_} = validation_data {
-                let offer_author_address: Address = get_author(&commitment.offer_address)?.into();
-                let offer: Offer = handle_get_offer(commitment.offer_address.clone())?;
                 let commitment_author_address: Address = author_from_header(&validation_.package.chain_header)?;
-
-                assert!(offer_author_address == commitment.host_id);
-                assert!(commitment_author_address == offer.challenger_id);
-                Ok(())
+                validate_commitment(&commitment, &commitment_author_address)
             } else { Err(String::from("Unreachable").into()) }
         }
     )
@@ -262,338 +843,4504 @@ fn define_move_entry() -> ValidatingEntryType {
             // move.author == commitment.host_id, challenger_id == commitment.author, 
             // move.component is in format
             if let hdk::EntryValidationData::Create{entry: move_, validation_data: validation_} = validation_data {
-                let commitment_author_address: Address = get_author(&move_.commitment_address)?.into();
-                let commitment: Commitment = handle_get_commitment(move_.commitment_address.clone())?;
                 let move_author_address: Address = author_from_header(&validation_.package.chain_header)?;
-                
-                assert!(move_author_address == commitment.host_id);
-                assert!(commitment_author_address == move_.challenger_id);
-                // TODO assert(_move.component is not in format);
-                Ok(())
+                validate_move(&move_, &move_author_address)
             } else { Err(String::from("Unreachable").into()) }
-        }
+        },
+        links: [
+            to!(
+                "game_result",
+                tag: "game_result",
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: |validation_data: hdk::LinkValidationData| {
+                    // the target is about this move, and linked by its own author
+                    validate_move_link(validation_data)
+                }
+            ),
+            to!(
+                "forfeit_claim",
+                tag: "forfeit_claim",
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: |validation_data: hdk::LinkValidationData| {
+                    // the target is about this move, and linked by its own author
+                    validate_move_link(validation_data)
+                }
+            ),
+            to!(
+                "round_reveal",
+                tag: "round_reveal",
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: |validation_data: hdk::LinkValidationData| {
+                    // the target is about this move, and linked by its own author
+                    validate_move_link(validation_data)
+                }
+            )
+        ]
     )
 }
 
-fn define_game_result_entry() -> ValidatingEntryType {
+fn define_sealed_move_entry() -> ValidatingEntryType {
     entry!(
-        name: "game_result",
-        description: "challenger reveals a move that matches the hash commitment and commits a game result",
+        name: "sealed_move",
+        description: "host submits a hash of their move in response to the challenger's hash (symmetric protocol)",
         sharing: Sharing::Public,
         validation_package: || {
             hdk::ValidationPackageDefinition::Entry
         },
-        validation: |validation_data: hdk::EntryValidationData<GameResult>| {
-            // hash of reveal == move_.hash, reveal.component is in format
-            if let hdk::EntryValidationData::Create{entry: game_result, validation_data: validation_} = validation_data {
-                let result_author_address: Address = author_from_header(&validation_.package.chain_header)?;
-                match game_result.clone() {
-                    GameResult::Win {
-                        reveal,
-                        move_address,
-                        winner_id: _, // validated by checking game result
-                        loser_id: _,  // validated by checking game result
-                        format_id,
-                    } => validate_game_result(game_result, reveal, move_address, result_author_address),
-                    GameResult::Draw {
-                        reveal,
-                        move_address,
-                        players: _, // validated by checking game result
-                        format_id,
-                    } => validate_game_result(game_result, reveal, move_address, result_author_address),
+        validation: |validation_data: hdk::EntryValidationData<SealedMove>| {
+            // sealed_move.author == commitment.host_id, challenger_id == commitment.author,
+            // sealed_move.hash == commitment.hash, offer uses the symmetric protocol
+            if let hdk::EntryValidationData::Create{entry: sealed_move, validation_data: validation_} = validation_data {
+                let sealed_move_author_address: Address = author_from_header(&validation_.package.chain_header)?;
+                validate_sealed_move(&sealed_move, &sealed_move_author_address)
+            } else { Err(String::from("Unreachable").into()) }
+        },
+        links: [
+            to!(
+                "move_reveal",
+                tag: "move_reveal",
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: |_validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            ),
+            to!(
+                "game_result",
+                tag: "game_result",
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: |validation_data: hdk::LinkValidationData| {
+                    // the target is about this move, and linked by its own author
+                    validate_move_link(validation_data)
                 }
+            ),
+            to!(
+                "forfeit_claim",
+                tag: "forfeit_claim",
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: |validation_data: hdk::LinkValidationData| {
+                    // the target is about this move, and linked by its own author
+                    validate_move_link(validation_data)
+                }
+            )
+        ]
+    )
+}
+
+fn define_move_reveal_entry() -> ValidatingEntryType {
+    entry!(
+        name: "move_reveal",
+        description: "a player reveals the move behind their hash in the symmetric protocol",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |validation_data: hdk::EntryValidationData<MoveReveal>| {
+            // move_reveal.author is a player, hash of reveal == that player's hash
+            if let hdk::EntryValidationData::Create{entry: move_reveal, validation_data: validation_} = validation_data {
+                let reveal_author_address: Address = author_from_header(&validation_.package.chain_header)?;
+                validate_move_reveal(move_reveal, reveal_author_address)
             } else { Err(String::from("Unreachable").into()) }
         }
     )
 }
 
-// Public functions
-
-pub fn handle_new_offer(challenger_id_: Address, format_id_: String) -> ZomeApiResult<Address> {
-    let offer = Offer {
-        challenger_id: challenger_id_,
-        format_id: format_id_,
-    };
-
-    let entry = Entry::App("offer".into(), offer.into());
-    let address = hdk::commit_entry(&entry)?;
-    Ok(address)
+fn define_reveal_secret_entry() -> ValidatingEntryType {
+    entry!(
+        name: "reveal_secret",
+        description: "an agent keeps the reveal behind their own hash",
+        sharing: Sharing::Private,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |_validation_data: hdk::EntryValidationData<RevealSecret>| {
+            // only ever on the committing agent's own chain, checked when the reveal is published
+            Ok(())
+        }
+    )
 }
 
-pub fn handle_new_commitment(component_: Component, offer_address_: Address, host_id_: Address, nonce_: String) -> ZomeApiResult<Address> {
-    let offer: Offer = handle_get_offer(offer_address_.clone())?;
-    // let nonce_: String = String::from("randomstring"); // generate_nonce(); // We've decided this can be handled client-side.
-    let reveal = Reveal { component: component_, nonce: nonce_};
-        // this reveal needs to get stored locally somehow (not available publicly on chain)
-    let hashstring: HashString = calculate_hash(reveal);
+fn define_nonce_counter_entry() -> ValidatingEntryType {
+    entry!(
+        name: "nonce_counter",
+        description: "an agent keeps the counter for their next derived nonce",
+        sharing: Sharing::Private,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |_validation_data: hdk::EntryValidationData<NonceCounter>| {
+            // only ever on the agent's own chain, a stale counter would only repeat the agent's own nonces
+            Ok(())
+        }
+    )
+}
+
+fn define_escrowed_reveal_entry() -> ValidatingEntryType {
+    entry!(
+        name: "escrowed_reveal",
+        description: "arbiter keeps a challenger's reveal in case the challenger stalls",
+        sharing: Sharing::Private,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |_validation_data: hdk::EntryValidationData<EscrowedReveal>| {
+            // only ever on the arbiter's own chain, checked when the arbiter commits a game result
+            Ok(())
+        }
+    )
+}
+
+fn define_game_result_entry() -> ValidatingEntryType {
+    entry!(
+        name: "game_result",
+        description: "challenger reveals a move that matches the hash commitment and commits a game result",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |validation_data: hdk::EntryValidationData<GameResult>| {
+            // hash of reveal == move_.hash, reveal.component is in format
+            if let hdk::EntryValidationData::Create{entry: game_result, validation_data: validation_} = validation_data {
+                validate_game_result_entry(game_result, &validation_.package.chain_header)
+            } else { Err(String::from("Unreachable").into()) }
+        },
+        links: [
+            to!(
+                "result_confirmation",
+                tag: "result_confirmation",
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: |validation_data: hdk::LinkValidationData| {
+                    // the confirmation is of this result, linked by the opponent of the player who reported it
+                    validate_result_confirmation_link(validation_data)
+                }
+            )
+        ]
+    )
+}
+
+fn define_result_confirmation_entry() -> ValidatingEntryType {
+    entry!(
+        name: "result_confirmation",
+        description: "the other player countersigns a game result",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |validation_data: hdk::EntryValidationData<ResultConfirmation>| {
+            // confirmation.author == the player who did not report the result, signature matches
+            if let hdk::EntryValidationData::Create{entry: confirmation, validation_data: validation_} = validation_data {
+                let confirmation_author_address: Address = author_from_header(&validation_.package.chain_header)?;
+                validate_result_confirmation(confirmation, confirmation_author_address)
+            } else { Err(String::from("Unreachable").into()) }
+        }
+    )
+}
+
+fn define_forfeit_claim_entry() -> ValidatingEntryType {
+    entry!(
+        name: "forfeit_claim",
+        description: "a player claims a win because their opponent did not reveal before the offer's deadline",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |validation_data: hdk::EntryValidationData<ForfeitClaim>| {
+            // claim.author == claim.winner_id is a player who may claim, deadline has passed
+            if let hdk::EntryValidationData::Create{entry: claim, validation_data: validation_} = validation_data {
+                validate_forfeit_claim(claim, &validation_.package.chain_header)
+            } else { Err(String::from("Unreachable").into()) }
+        }
+    )
+}
+
+fn define_dispute_entry() -> ValidatingEntryType {
+    entry!(
+        name: "dispute",
+        description: "a player disputes a commitment, move or game result of their game",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |validation_data: hdk::EntryValidationData<Dispute>| {
+            // dispute.author is a player of the subject's game, verdict == verdict of re-running the subject's checks
+            if let hdk::EntryValidationData::Create{entry: dispute, validation_data: validation_} = validation_data {
+                validate_dispute(dispute, &validation_.package.chain_header)
+            } else { Err(String::from("Unreachable").into()) }
+        },
+        links: [
+            from!(
+                "commitment",
+                tag: "dispute",
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: |validation_data: hdk::LinkValidationData| {
+                    // the dispute is against the base, and linked by its own author
+                    validate_dispute_link(validation_data)
+                }
+            ),
+            from!(
+                "move",
+                tag: "dispute",
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: |validation_data: hdk::LinkValidationData| {
+                    // the dispute is against the base, and linked by its own author
+                    validate_dispute_link(validation_data)
+                }
+            ),
+            from!(
+                "sealed_move",
+                tag: "dispute",
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: |validation_data: hdk::LinkValidationData| {
+                    // the dispute is against the base, and linked by its own author
+                    validate_dispute_link(validation_data)
+                }
+            ),
+            from!(
+                "game_result",
+                tag: "dispute",
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: |validation_data: hdk::LinkValidationData| {
+                    // the dispute is against the base, and linked by its own author
+                    validate_dispute_link(validation_data)
+                }
+            ),
+            from!(
+                "forfeit_claim",
+                tag: "dispute",
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: |validation_data: hdk::LinkValidationData| {
+                    // the dispute is against the base, and linked by its own author
+                    validate_dispute_link(validation_data)
+                }
+            )
+        ]
+    )
+}
+
+fn define_anchor_entry() -> ValidatingEntryType {
+    entry!(
+        name: "anchor",
+        description: "a well-known entry that agents, outcomes or rating snapshots are linked from",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |_validation_data: hdk::EntryValidationData<Anchor>| {
+            Ok(())
+        },
+        links: [
+            to!(
+                "%agent_id",
+                tag: "player",
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: |validation_data: hdk::LinkValidationData| {
+                    // agents only join a player index themselves
+                    validate_player_link(validation_data)
+                }
+            ),
+            to!(
+                "game_result",
+                tag: "outcome",
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: |validation_data: hdk::LinkValidationData| {
+                    // base is the day anchor of the outcome's format and header timestamp
+                    validate_outcome_link(validation_data)
+                }
+            ),
+            to!(
+                "forfeit_claim",
+                tag: "outcome",
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: |validation_data: hdk::LinkValidationData| {
+                    // base is the day anchor of the outcome's format and header timestamp
+                    validate_outcome_link(validation_data)
+                }
+            ),
+            to!(
+                "anchor",
+                tag: "outcome_day",
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: |validation_data: hdk::LinkValidationData| {
+                    // target is a day anchor of the format the base indexes
+                    validate_outcome_day_link(validation_data)
+                }
+            ),
+            to!(
+                "rating_snapshot",
+                tag: "rating_snapshot",
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: |validation_data: hdk::LinkValidationData| {
+                    // base is the snapshot index of the snapshot's format
+                    validate_rating_snapshot_link(validation_data)
+                }
+            )
+        ]
+    )
+}
+
+fn define_jury_entry() -> ValidatingEntryType {
+    entry!(
+        name: "jury",
+        description: "jurors drawn from the player index to decide an inconclusive dispute",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |validation_data: hdk::EntryValidationData<Jury>| {
+            // dispute is inconclusive, jurors == select_jurors(dispute)
+            if let hdk::EntryValidationData::Create{entry: jury, validation_data: _} = validation_data {
+                validate_jury(jury)
+            } else { Err(String::from("Unreachable").into()) }
+        },
+        links: [
+            from!(
+                "dispute",
+                tag: "jury",
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: |validation_data: hdk::LinkValidationData| {
+                    // the target belongs to the base, and is linked by its own author
+                    validate_jury_link(validation_data)
+                }
+            )
+        ]
+    )
+}
+
+fn define_vote_commitment_entry() -> ValidatingEntryType {
+    entry!(
+        name: "vote_commitment",
+        description: "a juror commits to a hidden vote",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |validation_data: hdk::EntryValidationData<VoteCommitment>| {
+            // vote_commitment.author is a juror who has not voted yet
+            if let hdk::EntryValidationData::Create{entry: vote_commitment, validation_data: validation_} = validation_data {
+                let juror: Address = author_from_header(&validation_.package.chain_header)?;
+                validate_vote_commitment(vote_commitment, juror)
+            } else { Err(String::from("Unreachable").into()) }
+        },
+        links: [
+            from!(
+                "jury",
+                tag: "vote_commitment",
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: |validation_data: hdk::LinkValidationData| {
+                    // the target belongs to the base, and is linked by its own author
+                    validate_jury_link(validation_data)
+                }
+            )
+        ]
+    )
+}
+
+fn define_vote_reveal_entry() -> ValidatingEntryType {
+    entry!(
+        name: "vote_reveal",
+        description: "a juror reveals their vote once every juror has committed",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |validation_data: hdk::EntryValidationData<VoteReveal>| {
+            // vote_reveal.author == vote_commitment.author, hash of vote reveal == vote_commitment.hash
+            if let hdk::EntryValidationData::Create{entry: vote_reveal, validation_data: validation_} = validation_data {
+                let juror: Address = author_from_header(&validation_.package.chain_header)?;
+                validate_vote_reveal(vote_reveal, juror)
+            } else { Err(String::from("Unreachable").into()) }
+        },
+        links: [
+            from!(
+                "vote_commitment",
+                tag: "vote_reveal",
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: |validation_data: hdk::LinkValidationData| {
+                    // the target belongs to the base, and is linked by its own author
+                    validate_jury_link(validation_data)
+                }
+            )
+        ]
+    )
+}
+
+fn define_jury_verdict_entry() -> ValidatingEntryType {
+    entry!(
+        name: "jury_verdict",
+        description: "the majority of a jury's votes decides an inconclusive dispute",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |validation_data: hdk::EntryValidationData<JuryVerdict>| {
+            // vote reveals are from distinct jurors of the jury, and a majority of them voted for the verdict
+            if let hdk::EntryValidationData::Create{entry: jury_verdict, validation_data: _} = validation_data {
+                validate_jury_verdict(jury_verdict)
+            } else { Err(String::from("Unreachable").into()) }
+        },
+        links: [
+            from!(
+                "dispute",
+                tag: "jury_verdict",
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: |validation_data: hdk::LinkValidationData| {
+                    // the target belongs to the base, and is linked by its own author
+                    validate_jury_link(validation_data)
+                }
+            )
+        ]
+    )
+}
+
+fn define_rating_snapshot_entry() -> ValidatingEntryType {
+    entry!(
+        name: "rating_snapshot",
+        description: "standings of a format as of a set of outcomes, so ratings need not be replayed from the start",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |validation_data: hdk::EntryValidationData<RatingSnapshot>| {
+            // standings == previous snapshot's standings with the listed outcomes replayed on top
+            if let hdk::EntryValidationData::Create{entry: snapshot, validation_data: _} = validation_data {
+                validate_rating_snapshot(snapshot)
+            } else { Err(String::from("Unreachable").into()) }
+        }
+    )
+}
+
+fn define_match_entry() -> ValidatingEntryType {
+    entry!(
+        name: "match",
+        description: "a participant starts a series of rounds decided by best-of-n or first-to-k",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |validation_data: hdk::EntryValidationData<Match>| {
+            // match.author is one of two distinct participants, the rule needs at least one round
+            // and a deck match at least one use of each component, constraints must be satisfiable
+            if let hdk::EntryValidationData::Create{entry: match_, validation_data: validation_} = validation_data {
+                let match_author_address: Address = author_from_header(&validation_.package.chain_header)?;
+                validate_match(match_, match_author_address)
+            } else { Err(String::from("Unreachable").into()) }
+        },
+        links: [
+            to!(
+                "offer",
+                tag: "round_offer",
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: |_validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            ),
+            to!(
+                "match_result",
+                tag: "match_result",
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: |_validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            ),
+            to!(
+                "match_commitment",
+                tag: "match_commitment",
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: |_validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            )
+        ]
+    )
+}
+
+fn define_match_result_entry() -> ValidatingEntryType {
+    entry!(
+        name: "match_result",
+        description: "a participant records who won a match once its rounds decide it",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |validation_data: hdk::EntryValidationData<MatchResult>| {
+            // match_result.author is a participant, match_result == tally of the round outcomes
+            if let hdk::EntryValidationData::Create{entry: match_result, validation_data: validation_} = validation_data {
+                let result_author_address: Address = author_from_header(&validation_.package.chain_header)?;
+                validate_match_result(match_result, result_author_address)
+            } else { Err(String::from("Unreachable").into()) }
+        }
+    )
+}
+
+fn define_match_commitment_entry() -> ValidatingEntryType {
+    entry!(
+        name: "match_commitment",
+        description: "a participant commits to their challenger moves for every round of a match under one Merkle root",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |validation_data: hdk::EntryValidationData<MatchCommitment>| {
+            // match_commitment.author is a participant with no other commitment to the match
+            if let hdk::EntryValidationData::Create{entry: match_commitment, validation_data: validation_} = validation_data {
+                let commitment_author_address: Address = author_from_header(&validation_.package.chain_header)?;
+                validate_match_commitment(&match_commitment, &commitment_author_address)
+            } else { Err(String::from("Unreachable").into()) }
+        }
+    )
+}
+
+fn define_round_reveal_entry() -> ValidatingEntryType {
+    entry!(
+        name: "round_reveal",
+        description: "the challenger reveals one round of their match commitment with an inclusion proof",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |validation_data: hdk::EntryValidationData<RoundReveal>| {
+            // round_reveal.author is the round's challenger, the proof leads from the reveal's leaf to the root
+            if let hdk::EntryValidationData::Create{entry: round_reveal, validation_data: validation_} = validation_data {
+                let reveal_author_address: Address = author_from_header(&validation_.package.chain_header)?;
+                validate_round_reveal(&round_reveal, &reveal_author_address)
+            } else { Err(String::from("Unreachable").into()) }
+        }
+    )
+}
+
+fn define_set_commitment_entry() -> ValidatingEntryType {
+    entry!(
+        name: "set_commitment",
+        description: "challenger agent accepts a game set offer by committing one move hash per game",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |validation_data: hdk::EntryValidationData<SetCommitment>| {
+            // offer.author == set_commitment.host, set_commitment.author == offer.challenger, one hash per game
+            if let hdk::EntryValidationData::Create{entry: set_commitment, validation_data: validation_} = validation_data {
+                let commitment_author_address: Address = author_from_header(&validation_.package.chain_header)?;
+                validate_set_commitment(&set_commitment, &commitment_author_address)
+            } else { Err(String::from("Unreachable").into()) }
+        }
+    )
+}
+
+fn define_set_move_entry() -> ValidatingEntryType {
+    entry!(
+        name: "set_move",
+        description: "host submits one move per game in response to the challenger's set commitment",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |validation_data: hdk::EntryValidationData<SetMove>| {
+            // set_move.author == set_commitment.host_id, challenger_id == set_commitment.author, one component per game
+            if let hdk::EntryValidationData::Create{entry: set_move, validation_data: validation_} = validation_data {
+                let move_author_address: Address = author_from_header(&validation_.package.chain_header)?;
+                validate_set_move(&set_move, &move_author_address)
+            } else { Err(String::from("Unreachable").into()) }
+        },
+        links: [
+            to!(
+                "set_result",
+                tag: "set_result",
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: |_validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            )
+        ]
+    )
+}
+
+fn define_set_result_entry() -> ValidatingEntryType {
+    entry!(
+        name: "set_result",
+        description: "challenger reveals every game of a set at once with the winner of each",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |validation_data: hdk::EntryValidationData<SetResult>| {
+            // set_result.author == challenger, every reveal matches its hash, winners follow from the components
+            if let hdk::EntryValidationData::Create{entry: set_result, validation_data: validation_} = validation_data {
+                let result_author_address: Address = author_from_header(&validation_.package.chain_header)?;
+                validate_set_result(set_result, &result_author_address)
+            } else { Err(String::from("Unreachable").into()) }
+        }
+    )
+}
+
+fn define_free_for_all_entry() -> ValidatingEntryType {
+    entry!(
+        name: "free_for_all",
+        description: "a player starts a game between any number of players",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |validation_data: hdk::EntryValidationData<FreeForAll>| {
+            // free_for_all.author is one of at least two distinct players
+            if let hdk::EntryValidationData::Create{entry: free_for_all, validation_data: validation_} = validation_data {
+                let game_author_address: Address = author_from_header(&validation_.package.chain_header)?;
+                validate_free_for_all(free_for_all, game_author_address)
+            } else { Err(String::from("Unreachable").into()) }
+        },
+        links: [
+            to!(
+                "free_for_all_commitment",
+                tag: "commitment",
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: |_validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            ),
+            to!(
+                "free_for_all_reveal",
+                tag: "reveal",
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: |_validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            ),
+            to!(
+                "free_for_all_ranking",
+                tag: "ranking",
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: |_validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            )
+        ]
+    )
+}
+
+fn define_free_for_all_commitment_entry() -> ValidatingEntryType {
+    entry!(
+        name: "free_for_all_commitment",
+        description: "a player of a free-for-all commits a move hash",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |validation_data: hdk::EntryValidationData<FreeForAllCommitment>| {
+            // commitment.author is a player with no other commitment to the game
+            if let hdk::EntryValidationData::Create{entry: commitment, validation_data: validation_} = validation_data {
+                let commitment_author_address: Address = author_from_header(&validation_.package.chain_header)?;
+                validate_free_for_all_commitment(&commitment, &commitment_author_address)
+            } else { Err(String::from("Unreachable").into()) }
+        }
+    )
+}
+
+fn define_free_for_all_reveal_entry() -> ValidatingEntryType {
+    entry!(
+        name: "free_for_all_reveal",
+        description: "a player of a free-for-all reveals their move once every player has committed",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |validation_data: hdk::EntryValidationData<FreeForAllReveal>| {
+            // reveal.author == commitment.author, every player has committed, hash of reveal == commitment.hash
+            if let hdk::EntryValidationData::Create{entry: free_for_all_reveal, validation_data: validation_} = validation_data {
+                let reveal_author_address: Address = author_from_header(&validation_.package.chain_header)?;
+                validate_free_for_all_reveal(&free_for_all_reveal, &reveal_author_address)
+            } else { Err(String::from("Unreachable").into()) }
+        }
+    )
+}
+
+fn define_free_for_all_ranking_entry() -> ValidatingEntryType {
+    entry!(
+        name: "free_for_all_ranking",
+        description: "a player ranks a free-for-all from every player's reveal",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |validation_data: hdk::EntryValidationData<FreeForAllRanking>| {
+            // ranking.author is a player, one reveal per player, scores and ranks follow from the reveals
+            if let hdk::EntryValidationData::Create{entry: ranking, validation_data: validation_} = validation_data {
+                let ranking_author_address: Address = author_from_header(&validation_.package.chain_header)?;
+                validate_free_for_all_ranking(ranking, &ranking_author_address)
+            } else { Err(String::from("Unreachable").into()) }
+        }
+    )
+}
+
+// Public functions
+
+pub fn handle_new_offer(challenger_id_: Address, format_id_: String, reveal_deadline_: u64, protocol_: Protocol, arbiter_id_: Option<Address>, match_address_: Option<Address>, games_: Option<u64>) -> ZomeApiResult<Address> {
+    // a round follows the decided result of the match's latest round
+    let (round_, previous_result_address_): (Option<u64>, Option<Address>) = match &match_address_ {
+        Some(match_address) => match handle_get_match_rounds(match_address.clone())?.last() {
+            Some(address) => (Some(handle_get_offer(address.clone())?.round.unwrap_or(0) + 1), Some(get_round_result_address(address)?)),
+            None => (Some(1), None),
+        },
+        None => (None, None),
+    };
+    let offer = Offer {
+        challenger_id: challenger_id_,
+        format_id: format_id_.clone(),
+        reveal_deadline: reveal_deadline_,
+        protocol: protocol_,
+        arbiter_id: arbiter_id_,
+        match_address: match_address_.clone(),
+        round: round_,
+        previous_result_address: previous_result_address_,
+        games: games_,
+    };
+
+    let entry = Entry::App("offer".into(), offer.into());
+    let address = hdk::commit_entry(&entry)?;
+    if let Some(match_address) = match_address_ {
+        hdk::link_entries(&match_address, &address, "round_offer")?;
+    }
+    join_player_index(&format_id_)?;
+    Ok(address)
+}
+
+pub fn handle_new_match(format_id: String, rule: MatchRule, participants: Vec<Address>, alternate_roles: Option<bool>, replay_draws: Option<bool>, component_limit: Option<u64>, constraints: Option<Vec<MoveConstraint>>) -> ZomeApiResult<Address> {
+    let match_ = Match {
+        format_id: format_id,
+        rule: rule,
+        participants: participants,
+        alternate_roles: alternate_roles.unwrap_or(false),
+        replay_draws: replay_draws.unwrap_or(false),
+        component_limit: component_limit,
+        constraints: constraints.unwrap_or_default(),
+    };
+
+    let entry = Entry::App("match".into(), match_.into());
+    let address = hdk::commit_entry(&entry)?;
+    Ok(address)
+}
+
+// Without a nonce, the zome derives one from the agent's signature
+pub fn handle_new_commitment(component_: Component, offer_address_: Address, host_id_: Address, nonce_: Option<String>, timelock_: Option<TimelockSetup>, hash_algorithm_: Option<HashAlgorithm>) -> ZomeApiResult<Address> {
+    let offer: Offer = handle_get_offer(offer_address_.clone())?;
+    let hash_algorithm: HashAlgorithm = choose_hash_algorithm(&offer.format_id, hash_algorithm_)?;
+    let (nonce_, nonce_counter) = match nonce_ {
+        Some(nonce) => (nonce, None),
+        None => {
+            let nonce_counter: u64 = next_nonce_counter()?;
+            (derive_nonce(&offer_address_, nonce_counter)?, Some(nonce_counter))
+        },
+    };
+    check_new_nonce(&nonce_)?;
+    let reveal = Reveal { component: component_, nonce: nonce_};
+    let hashstring: HashString = calculate_hash(&commitment_preimage(&reveal, &offer_address_, &offer.challenger_id), &hash_algorithm)?;
+    let timelock: Option<TimelockPuzzle> = match timelock_ {
+        Some(setup) => Some(seal_reveal(&reveal, setup)?),
+        None => None,
+    };
+
+    let mut commitment = Commitment {
+        hash: hashstring,
+        hash_algorithm: hash_algorithm,
+        offer_address: offer_address_,
+        host_id: host_id_,
+        format_id: offer.format_id.clone(),
+        timelock: timelock,
+        nonce_counter: nonce_counter,
+        signature: String::new(),
+    };
+    commitment.signature = sign_content(&commitment_content(&commitment))?;
+
+    let entry = Entry::App("commitment".into(), commitment.into());
+    if let Some(arbiter_id) = offer.arbiter_id {
+        // the arbiter must hold the reveal before the game can go ahead
+        let message = EscrowMessage {
+            reveal: reveal.clone(),
+            commitment_address: hdk::entry_address(&entry)?,
+        };
+        let response: String = hdk::send(arbiter_id, String::from(JsonString::from(message)), Timeout::default())?;
+        if response != String::from("Ok") {
+            return Err(format!("Arbiter did not accept reveal: {}", response).into());
+        }
+    }
+    let address = hdk::commit_entry(&entry)?;
+    commit_reveal_secret(reveal, address.clone())?;
+    join_player_index(&offer.format_id)?;
+    Ok(address)
+}
+
+// Commits to one component per round; the zome derives each round's nonce and keeps the reveals
+pub fn handle_new_match_commitment(match_address: Address, components: Vec<Component>, hash_algorithm_: Option<HashAlgorithm>) -> ZomeApiResult<Address> {
+    let match_: Match = handle_get_match(match_address.clone())?;
+    let hash_algorithm: HashAlgorithm = choose_hash_algorithm(&match_.format_id, hash_algorithm_)?;
+    let mut reveals: Vec<Reveal> = Vec::new();
+    for (index, component) in components.into_iter().enumerate() {
+        let nonce: String = derive_nonce(&match_address, index as u64 + 1)?;
+        reveals.push(Reveal { component: component, nonce: nonce });
+    }
+    let leaves: Vec<HashString> = round_leaves(&reveals, &match_address, &hdk::AGENT_ADDRESS, &hash_algorithm)?;
+    let root: HashString = match merkle::root(&leaves, &|left: &HashString, right: &HashString| merkle_node(left, right, &hash_algorithm))? {
+        Some(root) => root,
+        None => return Err(String::from("A match commitment needs at least one round").into()),
+    };
+
+    let mut match_commitment = MatchCommitment {
+        match_address: match_address.clone(),
+        root: root,
+        hash_algorithm: hash_algorithm,
+        rounds: reveals.len() as u64,
+        signature: String::new(),
+    };
+    match_commitment.signature = sign_content(&match_commitment_content(&match_commitment))?;
+
+    let entry = Entry::App("match_commitment".into(), match_commitment.into());
+    let address = hdk::commit_entry(&entry)?;
+    hdk::link_entries(&match_address, &address, "match_commitment")?;
+    for reveal in reveals {
+        commit_reveal_secret(reveal, address.clone())?;
+    }
+    join_player_index(&match_.format_id)?;
+    Ok(address)
+}
+
+// The host's move for a round whose challenger committed to the whole match
+pub fn handle_new_round_move(component: Component, offer_address: Address) -> ZomeApiResult<Address> {
+    let offer: Offer = handle_get_offer(offer_address.clone())?;
+    let match_address: Address = match &offer.match_address {
+        Some(match_address) => match_address.clone(),
+        None => return Err(String::from("Offer is not a round of a match").into()),
+    };
+    let commitment_address: Address = get_match_commitment_address(&match_address, &offer.challenger_id)?;
+    let match_commitment: MatchCommitment = handle_get_match_commitment(commitment_address.clone())?;
+
+    let mut move_ = Move {
+        component: component,
+        commitment_address: commitment_address,
+        challenger_id: offer.challenger_id,
+        hash: match_commitment.root,
+        format_id: offer.format_id,
+        offer_address: Some(offer_address.clone()),
+        signature: String::new(),
+    };
+    move_.signature = sign_content(&move_content(&move_))?;
+
+    let entry = Entry::App("move".into(), move_.into());
+    let address = hdk::commit_entry(&entry)?;
+    hdk::link_entries(&offer_address, &address, "move")?;
+    Ok(address)
+}
+
+// The challenger reveals their committed component for the round, with its inclusion proof
+pub fn handle_new_round_reveal(move_address: Address) -> ZomeApiResult<Address> {
+    let game: Game = get_game(&move_address)?;
+    let match_commitment: MatchCommitment = handle_get_match_commitment(game.commitment_address.clone())?;
+    let round: u64 = get_match_round(&match_commitment.match_address, &game.offer_address)?;
+    let leaf_rounds: Vec<u64> = (1..=match_commitment.rounds).collect();
+    let reveals: Vec<Reveal> = get_derived_reveals(&game.commitment_address, &match_commitment.match_address, &leaf_rounds)?;
+    let leaves: Vec<HashString> = round_leaves(&reveals, &match_commitment.match_address, &game.challenger_id, &match_commitment.hash_algorithm)?;
+    let index: usize = round as usize - 1;
+    let proof: Vec<HashString> = match merkle::proof(&leaves, index, &|left: &HashString, right: &HashString| merkle_node(left, right, &match_commitment.hash_algorithm))? {
+        Some(proof) => proof,
+        None => return Err(format!("Match commitment does not cover round {}", round).into()),
+    };
+    let round_reveal = RoundReveal {
+        reveal: reveals[index].clone(),
+        move_address: move_address.clone(),
+        round: round,
+        proof: proof,
+    };
+
+    let entry = Entry::App("round_reveal".into(), round_reveal.into());
+    let address = hdk::commit_entry(&entry)?;
+    hdk::link_entries(&move_address, &address, "round_reveal")?;
+    Ok(address)
+}
+
+// Commits to one component per game of a set offer; the zome derives each game's nonce and keeps the reveals
+pub fn handle_new_set_commitment(components: Vec<Component>, offer_address: Address, hash_algorithm_: Option<HashAlgorithm>) -> ZomeApiResult<Address> {
+    let host_id: Address = get_author(&offer_address)?;
+    let offer: Offer = handle_get_offer(offer_address.clone())?;
+    let hash_algorithm: HashAlgorithm = choose_hash_algorithm(&offer.format_id, hash_algorithm_)?;
+    let mut reveals: Vec<Reveal> = Vec::new();
+    let mut hashes: Vec<HashString> = Vec::new();
+    for (index, component) in components.into_iter().enumerate() {
+        let reveal = Reveal { component: component, nonce: derive_nonce(&offer_address, index as u64)? };
+        hashes.push(calculate_hash(&set_preimage(&reveal, &offer_address, &offer.challenger_id, index as u64), &hash_algorithm)?);
+        reveals.push(reveal);
+    }
+
+    let mut set_commitment = SetCommitment {
+        hashes: hashes,
+        hash_algorithm: hash_algorithm,
+        offer_address: offer_address,
+        host_id: host_id,
+        format_id: offer.format_id.clone(),
+        signature: String::new(),
+    };
+    set_commitment.signature = sign_content(&set_commitment_content(&set_commitment))?;
+
+    let entry = Entry::App("set_commitment".into(), set_commitment.into());
+    let address = hdk::commit_entry(&entry)?;
+    for reveal in reveals {
+        commit_reveal_secret(reveal, address.clone())?;
+    }
+    join_player_index(&offer.format_id)?;
+    Ok(address)
+}
+
+pub fn handle_new_set_move(components: Vec<Component>, commitment_address: Address) -> ZomeApiResult<Address> {
+    let challenger_id: Address = get_author(&commitment_address)?;
+    let set_commitment: SetCommitment = handle_get_set_commitment(commitment_address.clone())?;
+
+    let mut set_move = SetMove {
+        components: components,
+        commitment_address: commitment_address,
+        challenger_id: challenger_id,
+        format_id: set_commitment.format_id,
+        signature: String::new(),
+    };
+    set_move.signature = sign_content(&set_move_content(&set_move))?;
+
+    let entry = Entry::App("set_move".into(), set_move.into());
+    let address = hdk::commit_entry(&entry)?;
+    hdk::link_entries(&set_commitment.offer_address, &address, "move")?;
+    Ok(address)
+}
+
+// The challenger reveals every game of the set from their kept reveals
+pub fn handle_new_set_result(move_address: Address) -> ZomeApiResult<Address> {
+    let set_move: SetMove = handle_get_set_move(move_address.clone())?;
+    let set_commitment: SetCommitment = handle_get_set_commitment(set_move.commitment_address.clone())?;
+    let nonce_counters: Vec<u64> = (0..set_commitment.hashes.len() as u64).collect();
+    let reveals: Vec<Reveal> = get_derived_reveals(&set_move.commitment_address, &set_commitment.offer_address, &nonce_counters)?;
+    let set_result: SetResult = create_set_result(&set_move, &move_address, reveals)?;
+
+    let entry = Entry::App("set_result".into(), set_result.into());
+    let address = hdk::commit_entry(&entry)?;
+    hdk::link_entries(&move_address, &address, "set_result")?;
+    Ok(address)
+}
+
+pub fn handle_new_free_for_all(format_id: String, players: Vec<Address>) -> ZomeApiResult<Address> {
+    let free_for_all = FreeForAll {
+        format_id: format_id,
+        players: players,
+    };
+
+    let entry = Entry::App("free_for_all".into(), free_for_all.into());
+    let address = hdk::commit_entry(&entry)?;
+    Ok(address)
+}
+
+// Without a nonce, the zome derives one from the agent's signature
+pub fn handle_new_free_for_all_commitment(game_address: Address, component: Component, nonce_: Option<String>, hash_algorithm_: Option<HashAlgorithm>) -> ZomeApiResult<Address> {
+    let free_for_all: FreeForAll = handle_get_free_for_all(game_address.clone())?;
+    let hash_algorithm: HashAlgorithm = choose_hash_algorithm(&free_for_all.format_id, hash_algorithm_)?;
+    let nonce: String = match nonce_ {
+        Some(nonce) => nonce,
+        None => derive_nonce(&game_address, 0)?,
+    };
+    check_new_nonce(&nonce)?;
+    let reveal = Reveal { component: component, nonce: nonce };
+
+    let mut commitment = FreeForAllCommitment {
+        game_address: game_address.clone(),
+        hash: calculate_hash(&free_for_all_preimage(&reveal, &game_address, &hdk::AGENT_ADDRESS), &hash_algorithm)?,
+        hash_algorithm: hash_algorithm,
+        signature: String::new(),
+    };
+    commitment.signature = sign_content(&free_for_all_commitment_content(&commitment))?;
+
+    let entry = Entry::App("free_for_all_commitment".into(), commitment.into());
+    let address = hdk::commit_entry(&entry)?;
+    hdk::link_entries(&game_address, &address, "commitment")?;
+    commit_reveal_secret(reveal, address.clone())?;
+    join_player_index(&free_for_all.format_id)?;
+    Ok(address)
+}
+
+pub fn handle_new_free_for_all_reveal(commitment_address: Address) -> ZomeApiResult<Address> {
+    let commitment: FreeForAllCommitment = handle_get_free_for_all_commitment(commitment_address.clone())?;
+    let free_for_all_reveal = FreeForAllReveal {
+        reveal: get_reveal_secret(&commitment_address)?,
+        commitment_address: commitment_address,
+    };
+
+    let entry = Entry::App("free_for_all_reveal".into(), free_for_all_reveal.into());
+    let address = hdk::commit_entry(&entry)?;
+    hdk::link_entries(&commitment.game_address, &address, "reveal")?;
+    Ok(address)
+}
+
+pub fn handle_new_free_for_all_ranking(game_address: Address) -> ZomeApiResult<Address> {
+    let free_for_all: FreeForAll = handle_get_free_for_all(game_address.clone())?;
+    let mut reveals: Vec<(Address, Address)> = Vec::new();
+    for address in hdk::get_links(&game_address, "reveal")?.addresses().iter() {
+        reveals.push((handle_get_free_for_all_reveal(address.clone())?.commitment_address, address.clone()));
+    }
+    let mut reveal_addresses: Vec<Address> = Vec::new();
+    for player in free_for_all.players.iter() {
+        let commitment_address: Address = get_player_commitment(&game_address, player)?;
+        match reveals.iter().find(|(revealed, _)| *revealed == commitment_address) {
+            Some((_, address)) => reveal_addresses.push(address.clone()),
+            None => return Err(format!("{} has not revealed yet", player).into()),
+        }
+    }
+    let ranking: FreeForAllRanking = create_free_for_all_ranking(&game_address, reveal_addresses)?;
+
+    let entry = Entry::App("free_for_all_ranking".into(), ranking.into());
+    let address = hdk::commit_entry(&entry)?;
+    hdk::link_entries(&game_address, &address, "ranking")?;
+    Ok(address)
+}
+
+pub fn handle_new_move(component_: Component, commitment_address_: Address, challenger_id_: Address) -> ZomeApiResult<Address> {
+    let commitment: Commitment = handle_get_commitment(commitment_address_.clone())?;
+
+    let mut move_ = Move {
+        component: component_,
+        commitment_address: commitment_address_,
+        challenger_id: challenger_id_,
+        hash: commitment.hash.clone(),
+        format_id: commitment.format_id,
+        offer_address: None,
+        signature: String::new(),
+    };
+    move_.signature = sign_content(&move_content(&move_))?;
+
+    let entry = Entry::App("move".into(), move_.into());
+    let address = hdk::commit_entry(&entry)?;
+    hdk::link_entries(&commitment.offer_address, &address, "move")?;
+    Ok(address)
+}
+
+// Without a nonce, the zome derives one from the agent's signature
+pub fn handle_new_sealed_move(component_: Component, commitment_address_: Address, challenger_id_: Address, nonce_: Option<String>) -> ZomeApiResult<Address> {
+    let commitment: Commitment = handle_get_commitment(commitment_address_.clone())?;
+    let (nonce_, nonce_counter) = match nonce_ {
+        Some(nonce) => (nonce, None),
+        None => {
+            let nonce_counter: u64 = next_nonce_counter()?;
+            (derive_nonce(&commitment.offer_address, nonce_counter)?, Some(nonce_counter))
+        },
+    };
+    check_new_nonce(&nonce_)?;
+    let reveal = Reveal { component: component_, nonce: nonce_};
+
+    let mut sealed_move = SealedMove {
+        host_hash: calculate_hash(&host_preimage(&reveal, &commitment.offer_address, &challenger_id_, &commitment.host_id), &commitment.hash_algorithm)?,
+        commitment_address: commitment_address_,
+        challenger_id: challenger_id_,
+        hash: commitment.hash.clone(),
+        format_id: commitment.format_id,
+        nonce_counter: nonce_counter,
+        signature: String::new(),
+    };
+    sealed_move.signature = sign_content(&sealed_move_content(&sealed_move))?;
+
+    let entry = Entry::App("sealed_move".into(), sealed_move.into());
+    let address = hdk::commit_entry(&entry)?;
+    commit_reveal_secret(reveal, address.clone())?;
+    hdk::link_entries(&commitment.offer_address, &address, "move")?;
+    Ok(address)
+}
+
+// Without a reveal, the agent's own reveal for the game is recovered (see recover_reveal)
+pub fn handle_new_move_reveal(reveal: Option<Reveal>, move_address: Address, component: Option<Component>) -> ZomeApiResult<Address> {
+    let reveal: Reveal = match reveal {
+        Some(reveal) => reveal,
+        None => {
+            let game: Game = get_game(&move_address)?;
+            if hdk::AGENT_ADDRESS.clone() == game.host_id {
+                recover_reveal(&move_address, component)?
+            } else {
+                recover_reveal(&game.commitment_address, component)?
+            }
+        },
+    };
+    let move_reveal = MoveReveal {
+        reveal: reveal,
+        move_address: move_address.clone(),
+    };
+
+    let entry = Entry::App("move_reveal".into(), move_reveal.into());
+    let address = hdk::commit_entry(&entry)?;
+    hdk::link_entries(&move_address, &address, "move_reveal")?;
+    Ok(address)
+}
+
+// Without a reveal, the challenger's reveal for the commitment is recovered (see recover_reveal)
+pub fn handle_new_game_result(move_address: Address, reveal: Option<Reveal>, component: Option<Component>) -> ZomeApiResult<Address> {
+    let game: Game = get_game(&move_address)?;
+    let reveal: Reveal = match reveal {
+        Some(reveal) => reveal,
+        None if game.match_committed => get_round_reveal(&move_address, &game)?.reveal,
+        None => recover_reveal(&game.commitment_address, component)?,
+    };
+    let game_result: GameResult = create_game_result(reveal, move_address.clone(), game.host_id.clone(), None, None)?;
+
+    commit_game_result(game_result, &game, &move_address)
+}
+
+// Either player can settle a symmetric game once both reveals are published
+pub fn handle_new_symmetric_game_result(move_address: Address) -> ZomeApiResult<Address> {
+    let game: Game = get_game(&move_address)?;
+    let mut host_reveal: Option<Reveal> = None;
+    let mut challenger_reveal: Option<Reveal> = None;
+    for (author, move_reveal) in get_move_reveals(&move_address)? {
+        if author == game.host_id {
+            host_reveal = Some(move_reveal.reveal);
+        } else if author == game.challenger_id {
+            challenger_reveal = Some(move_reveal.reveal);
+        }
+    }
+    let (challenger_reveal, host_reveal) = match (challenger_reveal, host_reveal) {
+        (Some(challenger_reveal), Some(host_reveal)) => (challenger_reveal, host_reveal),
+        _ => return Err(String::from("Both players have not revealed yet").into()),
+    };
+    let game_result: GameResult = create_game_result(challenger_reveal, move_address.clone(), game.host_id.clone(), Some(host_reveal), None)?;
+
+    commit_game_result(game_result, &game, &move_address)
+}
+
+// The arbiter settles a game with the challenger's escrowed reveal
+pub fn handle_new_arbitrated_game_result(move_address: Address) -> ZomeApiResult<Address> {
+    let game: Game = get_game(&move_address)?;
+    let reveal: Reveal = get_escrowed_reveal(&game.commitment_address)?;
+    let host_reveal: Option<Reveal> = get_host_reveal(&game, &move_address)?;
+    let game_result: GameResult = create_game_result(reveal, move_address.clone(), game.host_id.clone(), host_reveal, None)?;
+
+    commit_game_result(game_result, &game, &move_address)
+}
+
+// Any agent can settle a time-locked game after the reveal deadline by solving the puzzle
+pub fn handle_new_unsealed_game_result(move_address: Address) -> ZomeApiResult<Address> {
+    let game: Game = get_game(&move_address)?;
+    let puzzle: TimelockPuzzle = match game.timelock.clone() {
+        Some(puzzle) => puzzle,
+        None => return Err(String::from("Commitment is not time-locked").into()),
+    };
+    let key = timelock::solve(
+        &timelock::parse_number(&puzzle.modulus)?,
+        &timelock::parse_number(&puzzle.base)?,
+        puzzle.squarings,
+    )?;
+    let puzzle_solution: String = key.to_str_radix(10);
+    let reveal: Reveal = unseal_reveal(&puzzle, &puzzle_solution)?;
+    let host_reveal: Option<Reveal> = get_host_reveal(&game, &move_address)?;
+    let game_result: GameResult = create_game_result(reveal, move_address.clone(), game.host_id.clone(), host_reveal, Some(puzzle_solution))?;
+
+    commit_game_result(game_result, &game, &move_address)
+}
+
+pub fn handle_new_result_confirmation(game_result_address: Address) -> ZomeApiResult<Address> {
+    let confirmation = ResultConfirmation {
+        signature: sign_content(&confirmation_content(&game_result_address))?,
+        game_result_address: game_result_address.clone(),
+    };
+
+    let entry = Entry::App("result_confirmation".into(), confirmation.into());
+    let address = hdk::commit_entry(&entry)?;
+    hdk::link_entries(&game_result_address, &address, "result_confirmation")?;
+    Ok(address)
+}
+
+pub fn handle_new_forfeit_claim(move_address: Address) -> ZomeApiResult<Address> {
+    let game: Game = get_game(&move_address)?;
+    let winner_id: Address = hdk::AGENT_ADDRESS.clone();
+    let loser_id: Address = if winner_id == game.host_id { game.challenger_id } else { game.host_id };
+
+    let claim = ForfeitClaim {
+        move_address: move_address.clone(),
+        winner_id: winner_id,
+        loser_id: loser_id,
+        format_id: game.format_id.clone(),
+    };
+
+    let entry = Entry::App("forfeit_claim".into(), claim.into());
+    let address = hdk::commit_entry(&entry)?;
+    hdk::link_entries(&move_address, &address, "forfeit_claim")?;
+    index_outcome(&game.format_id, &address)?;
+    Ok(address)
+}
+
+pub fn handle_new_dispute(subject_address: Address, reason: DisputeReason, referenced_addresses: Vec<Address>) -> ZomeApiResult<Address> {
+    let dispute = Dispute {
+        verdict: evaluate_dispute(&subject_address, &reason)?,
+        juror_pool: get_juror_pool(&subject_address)?,
+        subject_address: subject_address.clone(),
+        reason: reason,
+        referenced_addresses: referenced_addresses,
+    };
+
+    let entry = Entry::App("dispute".into(), dispute.into());
+    let address = hdk::commit_entry(&entry)?;
+    hdk::link_entries(&subject_address, &address, "dispute")?;
+    Ok(address)
+}
+
+pub fn handle_new_jury(dispute_address: Address) -> ZomeApiResult<Address> {
+    let dispute: Dispute = handle_get_dispute(dispute_address.clone())?;
+    let jury = Jury {
+        jurors: select_jurors(&dispute)?,
+        dispute_address: dispute_address.clone(),
+    };
+
+    let entry = Entry::App("jury".into(), jury.into());
+    let address = hdk::commit_entry(&entry)?;
+    hdk::link_entries(&dispute_address, &address, "jury")?;
+    Ok(address)
+}
+
+// The nonce is derived from the juror's signature, so only the vote has to be given again on reveal
+pub fn handle_new_vote_commitment(jury_address: Address, vote: JuryVote) -> ZomeApiResult<Address> {
+    let nonce: String = derive_nonce(&jury_address, 0)?;
+    let vote_commitment = VoteCommitment {
+        hash: calculate_hash(&vote_preimage(&vote, &nonce, &jury_address, &hdk::AGENT_ADDRESS), &HashAlgorithm::Sha2256)?,
+        jury_address: jury_address.clone(),
+    };
+
+    let entry = Entry::App("vote_commitment".into(), vote_commitment.into());
+    let address = hdk::commit_entry(&entry)?;
+    hdk::link_entries(&jury_address, &address, "vote_commitment")?;
+    Ok(address)
+}
+
+pub fn handle_new_vote_reveal(vote_commitment_address: Address, vote: JuryVote) -> ZomeApiResult<Address> {
+    let vote_commitment: VoteCommitment = handle_get_vote_commitment(vote_commitment_address.clone())?;
+    let vote_reveal = VoteReveal {
+        vote_commitment_address: vote_commitment_address.clone(),
+        vote: vote,
+        nonce: derive_nonce(&vote_commitment.jury_address, 0)?,
+    };
+
+    let entry = Entry::App("vote_reveal".into(), vote_reveal.into());
+    let address = hdk::commit_entry(&entry)?;
+    hdk::link_entries(&vote_commitment_address, &address, "vote_reveal")?;
+    Ok(address)
+}
+
+// Tallies the revealed votes of the dispute's jury, once a majority of jurors agree
+pub fn handle_new_jury_verdict(dispute_address: Address) -> ZomeApiResult<Address> {
+    let mut jury_addresses: Vec<Address> = Vec::new();
+    for address in hdk::get_links(&dispute_address, "jury")?.addresses().iter() {
+        if handle_get_jury(address.clone())?.dispute_address == dispute_address {
+            jury_addresses.push(address.clone());
+        }
+    }
+    let jury_address: Address = match jury_addresses.into_iter().next() {
+        Some(address) => address,
+        None => return Err(String::from("Dispute has no jury").into()),
+    };
+    let jury: Jury = handle_get_jury(jury_address.clone())?;
+    let mut vote_reveals: Vec<(Address, VoteReveal)> = Vec::new();
+    for (vote_commitment_address, _) in get_vote_commitments(&jury_address, &jury)? {
+        for vote_reveal_address in hdk::get_links(&vote_commitment_address, "vote_reveal")?.addresses().iter() {
+            let vote_reveal: VoteReveal = handle_get_vote_reveal(vote_reveal_address.clone())?;
+            if vote_reveal.vote_commitment_address == vote_commitment_address {
+                vote_reveals.push((vote_reveal_address.clone(), vote_reveal));
+                break;
+            }
+        }
+    }
+    let majority: usize = jury.jurors.len() / 2 + 1;
+    let count = |vote: JuryVote| vote_reveals.iter().filter(|(_, vote_reveal)| vote_reveal.vote == vote).count();
+    let vote: JuryVote = if count(JuryVote::Uphold) >= majority {
+        JuryVote::Uphold
+    } else if count(JuryVote::Reject) >= majority {
+        JuryVote::Reject
+    } else {
+        return Err(String::from("No majority of jurors has revealed the same vote").into());
+    };
+
+    let jury_verdict = JuryVerdict {
+        dispute_address: dispute_address.clone(),
+        jury_address: jury_address,
+        verdict: jury_vote_verdict(&vote),
+        vote_reveal_addresses: vote_reveals
+            .into_iter()
+            .filter(|(_, vote_reveal)| vote_reveal.vote == vote)
+            .map(|(address, _)| address)
+            .collect(),
+    };
+
+    let entry = Entry::App("jury_verdict".into(), jury_verdict.into());
+    let address = hdk::commit_entry(&entry)?;
+    hdk::link_entries(&dispute_address, &address, "jury_verdict")?;
+    Ok(address)
+}
+
+// After a drawn round of a match that replays draws, the caller offers the replay and hosts it
+pub fn handle_new_replay_offer(move_address: Address) -> ZomeApiResult<Address> {
+    let game: Game = get_game(&move_address)?;
+    let match_address: Address = match &game.offer.match_address {
+        Some(match_address) => match_address.clone(),
+        None => return Err(String::from("Move is not a round of a match").into()),
+    };
+    let match_: Match = handle_get_match(match_address.clone())?;
+    if !match_.replay_draws {
+        return Err(String::from("Match does not replay draws").into());
+    }
+    if handle_get_match_rounds(match_address.clone())?.last() != Some(&game.offer_address) {
+        return Err(String::from("Move is not from the match's latest round").into());
+    }
+    match get_decided_outcome(&move_address)? {
+        Some((_, Outcome::Draw { .. })) => (),
+        _ => return Err(String::from("Round has not been drawn").into()),
+    }
+    let challenger_id: Address = if hdk::AGENT_ADDRESS.clone() == game.host_id { game.challenger_id.clone() } else { game.host_id.clone() };
+    handle_new_offer(
+        challenger_id,
+        game.offer.format_id.clone(),
+        game.offer.reveal_deadline,
+        game.offer.protocol.clone(),
+        game.offer.arbiter_id.clone(),
+        Some(match_address),
+        None,
+    )
+}
+
+// Either participant records the result once the match's rounds have decided it
+pub fn handle_new_match_result(match_address: Address) -> ZomeApiResult<Address> {
+    let match_result: MatchResult = tally_match(&match_address)?;
+
+    let entry = Entry::App("match_result".into(), match_result.into());
+    let address = hdk::commit_entry(&entry)?;
+    hdk::link_entries(&match_address, &address, "match_result")?;
+    Ok(address)
+}
+
+// Lets clients compute and check commitment hashes without reimplementing the canonical encoding
+pub fn handle_compute_commitment_hash(reveal: Reveal, offer_address: Address, challenger_id: Address, hash_algorithm: HashAlgorithm) -> ZomeApiResult<HashString> {
+    calculate_hash(&commitment_preimage(&reveal, &offer_address, &challenger_id), &hash_algorithm)
+}
+
+pub fn handle_get_offer(address: Address) -> ZomeApiResult<Offer> {
+    match hdk::get_entry(&address) {
+        Ok(Some(Entry::App(_, api_result))) => Ok(api_result.try_into()?),
+        _ => Err(String::from("No offer found").into())
+    }
+}
+
+pub fn handle_get_commitment(address: Address) -> ZomeApiResult<Commitment> {
+    match hdk::get_entry(&address) {
+        Ok(Some(Entry::App(_, api_result))) => Ok(api_result.try_into()?),
+        _ => Err(String::from("No commitment found").into())
+    }
+}
+
+pub fn handle_get_move(address: Address) -> ZomeApiResult<Move> {
+    match hdk::get_entry(&address) {
+        Ok(Some(Entry::App(_, api_result))) => Ok(api_result.try_into()?),
+        _ => Err(String::from("No move found").into())
+    }
+}
+
+pub fn handle_get_sealed_move(address: Address) -> ZomeApiResult<SealedMove> {
+    match hdk::get_entry(&address) {
+        Ok(Some(Entry::App(_, api_result))) => Ok(api_result.try_into()?),
+        _ => Err(String::from("No sealed move found").into())
+    }
+}
+
+pub fn handle_get_move_reveal(address: Address) -> ZomeApiResult<MoveReveal> {
+    match hdk::get_entry(&address) {
+        Ok(Some(Entry::App(_, api_result))) => Ok(api_result.try_into()?),
+        _ => Err(String::from("No move reveal found").into())
+    }
+}
+
+pub fn handle_get_game_result(address: Address) -> ZomeApiResult<GameResult> {
+    match hdk::get_entry(&address) {
+        Ok(Some(Entry::App(_, api_result))) => Ok(api_result.try_into()?),
+        _ => Err(String::from("No game result found").into())
+    }
+}
+
+pub fn handle_get_result_confirmation(address: Address) -> ZomeApiResult<ResultConfirmation> {
+    match hdk::get_entry(&address) {
+        Ok(Some(Entry::App(_, api_result))) => Ok(api_result.try_into()?),
+        _ => Err(String::from("No result confirmation found").into())
+    }
+}
+
+pub fn handle_get_forfeit_claim(address: Address) -> ZomeApiResult<ForfeitClaim> {
+    match hdk::get_entry(&address) {
+        Ok(Some(Entry::App(_, api_result))) => Ok(api_result.try_into()?),
+        _ => Err(String::from("No forfeit claim found").into())
+    }
+}
+
+pub fn handle_get_dispute(address: Address) -> ZomeApiResult<Dispute> {
+    match hdk::get_entry(&address) {
+        Ok(Some(Entry::App(_, api_result))) => Ok(api_result.try_into()?),
+        _ => Err(String::from("No dispute found").into())
+    }
+}
+
+pub fn handle_get_rating(agent_id: Address, format_id: String) -> ZomeApiResult<Rating> {
+    let system: Box<dyn RatingSystem> = rating_policy().system(&format_id);
+    let (standings, _) = replay_ratings(&format_id, system.as_ref(), true)?;
+    let rating: PlayerRating = standings.get(&agent_id).map(|standing| standing.player_rating()).unwrap_or_else(|| system.initial());
+    Ok(Rating {
+        provisional: system.is_provisional(&rating),
+        rating: rating.rating,
+        deviation: rating.deviation,
+        games: rating.games,
+    })
+}
+
+// The agent's rated games in the format, oldest first
+pub fn handle_get_rating_history(agent_id: Address, format_id: String) -> ZomeApiResult<Vec<RatingChange>> {
+    let (_, history) = replay_ratings(&format_id, rating_policy().system(&format_id).as_ref(), false)?;
+    Ok(history
+        .into_iter()
+        .filter(|(agent, _)| *agent == agent_id)
+        .map(|(_, change)| change)
+        .collect())
+}
+
+pub fn handle_get_match(address: Address) -> ZomeApiResult<Match> {
+    match hdk::get_entry(&address) {
+        Ok(Some(Entry::App(_, api_result))) => Ok(api_result.try_into()?),
+        _ => Err(String::from("No match found").into())
+    }
+}
+
+pub fn handle_get_match_commitment(address: Address) -> ZomeApiResult<MatchCommitment> {
+    match hdk::get_entry(&address) {
+        Ok(Some(Entry::App(_, api_result))) => Ok(api_result.try_into()?),
+        _ => Err(String::from("No match commitment found").into())
+    }
+}
+
+pub fn handle_get_round_reveal(address: Address) -> ZomeApiResult<RoundReveal> {
+    match hdk::get_entry(&address) {
+        Ok(Some(Entry::App(_, api_result))) => Ok(api_result.try_into()?),
+        _ => Err(String::from("No round reveal found").into())
+    }
+}
+
+pub fn handle_get_set_commitment(address: Address) -> ZomeApiResult<SetCommitment> {
+    match hdk::get_entry(&address) {
+        Ok(Some(Entry::App(_, api_result))) => Ok(api_result.try_into()?),
+        _ => Err(String::from("No set commitment found").into())
+    }
+}
+
+pub fn handle_get_set_move(address: Address) -> ZomeApiResult<SetMove> {
+    match hdk::get_entry(&address) {
+        Ok(Some(Entry::App(_, api_result))) => Ok(api_result.try_into()?),
+        _ => Err(String::from("No set move found").into())
+    }
+}
+
+pub fn handle_get_set_result(address: Address) -> ZomeApiResult<SetResult> {
+    match hdk::get_entry(&address) {
+        Ok(Some(Entry::App(_, api_result))) => Ok(api_result.try_into()?),
+        _ => Err(String::from("No set result found").into())
+    }
+}
+
+pub fn handle_get_free_for_all(address: Address) -> ZomeApiResult<FreeForAll> {
+    match hdk::get_entry(&address) {
+        Ok(Some(Entry::App(_, api_result))) => Ok(api_result.try_into()?),
+        _ => Err(String::from("No free-for-all found").into())
+    }
+}
+
+pub fn handle_get_free_for_all_commitment(address: Address) -> ZomeApiResult<FreeForAllCommitment> {
+    match hdk::get_entry(&address) {
+        Ok(Some(Entry::App(_, api_result))) => Ok(api_result.try_into()?),
+        _ => Err(String::from("No free-for-all commitment found").into())
+    }
+}
+
+pub fn handle_get_free_for_all_reveal(address: Address) -> ZomeApiResult<FreeForAllReveal> {
+    match hdk::get_entry(&address) {
+        Ok(Some(Entry::App(_, api_result))) => Ok(api_result.try_into()?),
+        _ => Err(String::from("No free-for-all reveal found").into())
+    }
+}
+
+pub fn handle_get_free_for_all_ranking(address: Address) -> ZomeApiResult<FreeForAllRanking> {
+    match hdk::get_entry(&address) {
+        Ok(Some(Entry::App(_, api_result))) => Ok(api_result.try_into()?),
+        _ => Err(String::from("No free-for-all ranking found").into())
+    }
+}
+
+pub fn handle_get_match_result(address: Address) -> ZomeApiResult<MatchResult> {
+    match hdk::get_entry(&address) {
+        Ok(Some(Entry::App(_, api_result))) => Ok(api_result.try_into()?),
+        _ => Err(String::from("No match result found").into())
+    }
+}
+
+// The match's round offers in order, each following a result of the round before it
+// Where several offers follow the same round, the lowest address is the round.
+pub fn handle_get_match_rounds(match_address: Address) -> ZomeApiResult<Vec<Address>> {
+    let mut offers: Vec<(u64, String, Address, Option<Address>)> = Vec::new();
+    for address in hdk::get_links(&match_address, "round_offer")?.addresses().iter() {
+        let offer: Offer = handle_get_offer(address.clone())?;
+        if offer.match_address.as_ref() == Some(&match_address) {
+            let previous_offer_address: Option<Address> = match &offer.previous_result_address {
+                Some(result_address) => Some(get_outcome_offer_address(result_address)?),
+                None => None,
+            };
+            offers.push((offer.round.unwrap_or(0), String::from(address.clone()), address.clone(), previous_offer_address));
+        }
+    }
+    offers.sort();
+    let mut rounds: Vec<Address> = Vec::new();
+    for (round, _, address, previous_offer_address) in offers {
+        if round == rounds.len() as u64 + 1 && previous_offer_address.as_ref() == rounds.last() {
+            rounds.push(address);
+        }
+    }
+    Ok(rounds)
+}
+
+// Agents who have played the format, best first by the format's rating system, LEADERBOARD_PAGE_SIZE to a page
+// Standings start from the latest rating snapshot, so only the outcomes since it are replayed
+pub fn handle_get_leaderboard(format_id: String, page: u64, min_games: Option<u64>) -> ZomeApiResult<Vec<LeaderboardEntry>> {
+    let system: Box<dyn RatingSystem> = rating_policy().system(&format_id);
+    let (standings, _) = replay_ratings(&format_id, system.as_ref(), true)?;
+
+    let mut agents: Vec<Address> = hdk::get_links(&hdk::entry_address(&format_player_index(&format_id))?, "player")?.addresses().to_vec();
+    agents.sort();
+    agents.dedup();
+    let mut leaderboard: Vec<(f64, LeaderboardEntry)> = Vec::new();
+    for agent_id in agents {
+        let standing: Standing = match standings.get(&agent_id) {
+            Some(standing) => standing.clone(),
+            None => Standing::new(agent_id.clone(), system.initial()),
+        };
+        if standing.games < min_games.unwrap_or(0) {
+            continue;
+        }
+        let rating: PlayerRating = standing.player_rating();
+        leaderboard.push((system.score(&rating), LeaderboardEntry {
+            provisional: system.is_provisional(&rating),
+            rating: standing.rating,
+            deviation: standing.deviation,
+            games: standing.games,
+            wins: standing.wins,
+            draws: standing.draws,
+            losses: standing.losses,
+            agent_id: agent_id,
+        }));
+    }
+    // agents were sorted by address, so the stable sort keeps ties in the same order for everyone
+    leaderboard.sort_by(|(score, _), (other_score, _)| other_score.partial_cmp(score).unwrap_or(std::cmp::Ordering::Equal));
+    Ok(leaderboard
+        .into_iter()
+        .skip(page as usize * LEADERBOARD_PAGE_SIZE)
+        .take(LEADERBOARD_PAGE_SIZE)
+        .map(|(_, entry)| entry)
+        .collect())
+}
+
+// Any agent can checkpoint a format's standings over the outcomes since the latest snapshot
+pub fn handle_new_rating_snapshot(format_id: String) -> ZomeApiResult<Address> {
+    let previous_snapshot_address: Option<Address> = handle_get_latest_rating_snapshot(format_id.clone())?;
+    let start: SnapshotChain = match &previous_snapshot_address {
+        Some(address) => get_snapshot_chain(address)?,
+        None => SnapshotChain::default(),
+    };
+    let outcomes: Vec<(u64, Address, Outcome)> = get_rated_outcomes(&format_id, start.last_covered.as_ref())?;
+    if outcomes.is_empty() {
+        return Err(String::from("No new outcomes since the latest snapshot").into());
+    }
+    let outcome_addresses: Vec<Address> = outcomes.iter().map(|(_, address, _)| address.clone()).collect();
+    let mut standings: BTreeMap<Address, Standing> = start.standings;
+    rate_outcomes(rating_policy().system(&format_id).as_ref(), &mut standings, &outcomes);
+
+    let snapshot = RatingSnapshot {
+        format_id: format_id.clone(),
+        previous_snapshot_address: previous_snapshot_address,
+        outcomes_hash: snapshot_hash(start.outcomes_hash.as_ref(), &outcome_addresses)?,
+        outcome_count: start.outcome_count + outcome_addresses.len() as u64,
+        outcome_addresses: outcome_addresses,
+        standings: standings.into_iter().map(|(_, standing)| standing).collect(),
+    };
+
+    let entry = Entry::App("rating_snapshot".into(), snapshot.into());
+    let address = hdk::commit_entry(&entry)?;
+    let anchor_address: Address = hdk::commit_entry(&rating_snapshot_index(&format_id))?;
+    hdk::link_entries(&anchor_address, &address, "rating_snapshot")?;
+    Ok(address)
+}
+
+pub fn handle_get_rating_snapshot(address: Address) -> ZomeApiResult<RatingSnapshot> {
+    match hdk::get_entry(&address) {
+        Ok(Some(Entry::App(_, api_result))) => Ok(api_result.try_into()?),
+        _ => Err(String::from("No rating snapshot found").into())
+    }
+}
+
+// The snapshot covering the most outcomes, ties broken by address
+pub fn handle_get_latest_rating_snapshot(format_id: String) -> ZomeApiResult<Option<Address>> {
+    let mut latest: Option<(u64, String, Address)> = None;
+    for address in hdk::get_links(&hdk::entry_address(&rating_snapshot_index(&format_id))?, "rating_snapshot")?.addresses().iter() {
+        let candidate = (handle_get_rating_snapshot(address.clone())?.outcome_count, String::from(address.clone()), address.clone());
+        if latest.as_ref().map_or(true, |current| (candidate.0, &candidate.1) > (current.0, &current.1)) {
+            latest = Some(candidate);
+        }
+    }
+    Ok(latest.map(|(_, _, address)| address))
+}
+
+// The jury's verdict if the dispute went to one, otherwise the verdict of the checks
+pub fn handle_get_dispute_verdict(dispute_address: Address) -> ZomeApiResult<Verdict> {
+    for jury_verdict_address in hdk::get_links(&dispute_address, "jury_verdict")?.addresses().iter() {
+        let jury_verdict: JuryVerdict = handle_get_jury_verdict(jury_verdict_address.clone())?;
+        if jury_verdict.dispute_address == dispute_address {
+            return Ok(jury_verdict.verdict);
+        }
+    }
+    Ok(handle_get_dispute(dispute_address)?.verdict)
+}
+
+pub fn handle_get_jury(address: Address) -> ZomeApiResult<Jury> {
+    match hdk::get_entry(&address) {
+        Ok(Some(Entry::App(_, api_result))) => Ok(api_result.try_into()?),
+        _ => Err(String::from("No jury found").into())
+    }
+}
+
+pub fn handle_get_vote_commitment(address: Address) -> ZomeApiResult<VoteCommitment> {
+    match hdk::get_entry(&address) {
+        Ok(Some(Entry::App(_, api_result))) => Ok(api_result.try_into()?),
+        _ => Err(String::from("No vote commitment found").into())
+    }
+}
+
+pub fn handle_get_vote_reveal(address: Address) -> ZomeApiResult<VoteReveal> {
+    match hdk::get_entry(&address) {
+        Ok(Some(Entry::App(_, api_result))) => Ok(api_result.try_into()?),
+        _ => Err(String::from("No vote reveal found").into())
+    }
+}
+
+pub fn handle_get_jury_verdict(address: Address) -> ZomeApiResult<JuryVerdict> {
+    match hdk::get_entry(&address) {
+        Ok(Some(Entry::App(_, api_result))) => Ok(api_result.try_into()?),
+        _ => Err(String::from("No jury verdict found").into())
+    }
+}
+
+// Addresses of the disputes filed against a commitment, move, game result or forfeit claim
+pub fn handle_get_disputes(address: Address) -> ZomeApiResult<Vec<Address>> {
+    let mut disputes: Vec<Address> = Vec::new();
+    for dispute_address in hdk::get_links(&address, "dispute")?.addresses().iter() {
+        if handle_get_dispute(dispute_address.clone())?.subject_address == address {
+            disputes.push(dispute_address.clone());
+        }
+    }
+    Ok(disputes)
+}
+
+// Whether any dispute against the game played on a move has been upheld
+pub fn handle_is_game_disputed(move_address: Address) -> ZomeApiResult<bool> {
+    let game: Game = get_game(&move_address)?;
+    let mut subject_addresses: Vec<Address> = vec![game.commitment_address, move_address.clone()];
+    subject_addresses.extend(get_game_results(&move_address)?.into_iter().map(|(address, _)| address));
+    subject_addresses.extend(get_forfeit_claims(&move_address)?.into_iter().map(|(address, _)| address));
+
+    for subject_address in subject_addresses.iter() {
+        if is_overturned(subject_address)? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+// The outcome of the game played on a move, if it has finished
+// Game results and forfeit claims overturned by an upheld dispute do not count
+pub fn handle_get_outcome(move_address: Address) -> ZomeApiResult<Option<Outcome>> {
+    Ok(get_decided_outcome(&move_address)?.map(|(_, outcome)| outcome))
+}
+
+// Whether the outcome of the game played on a move is only reported, or confirmed by both players
+pub fn handle_get_result_status(move_address: Address) -> ZomeApiResult<Option<ResultStatus>> {
+    for (result_address, _) in get_game_results(&move_address)? {
+        if is_overturned(&result_address)? {
+            continue;
+        }
+        let confirmer: Address = get_confirmer(&result_address)?;
+        for confirmation_address in hdk::get_links(&result_address, "result_confirmation")?.addresses().iter() {
+            if handle_get_result_confirmation(confirmation_address.clone())?.game_result_address == result_address
+                && get_author(confirmation_address)? == confirmer {
+                return Ok(Some(ResultStatus::Confirmed));
+            }
+        }
+        return Ok(Some(ResultStatus::Reported));
+    }
+    for (claim_address, _) in get_forfeit_claims(&move_address)? {
+        if !is_overturned(&claim_address)? {
+            return Ok(Some(ResultStatus::Reported));
+        }
+    }
+    Ok(None)
+}
+
+// Node-to-node messages are escrowed reveals sent to this agent as an arbiter
+pub fn handle_receive(from: Address, payload: String) -> String {
+    let message: EscrowMessage = match JsonString::from(payload).try_into() {
+        Ok(message) => message,
+        Err(_) => return String::from("Malformed escrow message"),
+    };
+    let escrowed_reveal = EscrowedReveal {
+        reveal: message.reveal,
+        commitment_address: message.commitment_address,
+        challenger_id: from,
+    };
+
+    let entry = Entry::App("escrowed_reveal".into(), escrowed_reveal.into());
+    match hdk::commit_entry(&entry) {
+        Ok(_) => String::from("Ok"),
+        Err(error) => String::from(error),
+    }
+}
+
+// this is not the correct way to handle agent ids
+/*
+pub fn handle_get_agent_id(address: Address) -> ZomeApiResult<AgentId> {
+    if let Ok(Some(agent_id_entry)) = hdk::get_entry(&address) {
+        match agent_id_entry {
+            Entry::AgentId(agent_id) => return Ok(agent_id),
+            _ => Err(String::from("Get AgentId failure: No agent found").into())
+        }
+    } else {
+        Err(String::from("Get AgentId failure: No entry found at address").into())
+    }
+}
+*/
+
+define_zome! {
+    entries: [
+        define_offer_entry(),
+        define_commitment_entry(),
+        define_move_entry(),
+        define_sealed_move_entry(),
+        define_move_reveal_entry(),
+        define_reveal_secret_entry(),
+        define_nonce_counter_entry(),
+        define_escrowed_reveal_entry(),
+        define_game_result_entry(),
+        define_result_confirmation_entry(),
+        define_forfeit_claim_entry(),
+        define_dispute_entry(),
+        define_anchor_entry(),
+        define_jury_entry(),
+        define_vote_commitment_entry(),
+        define_vote_reveal_entry(),
+        define_jury_verdict_entry(),
+        define_rating_snapshot_entry(),
+        define_match_entry(),
+        define_match_result_entry(),
+        define_match_commitment_entry(),
+        define_round_reveal_entry(),
+        define_set_commitment_entry(),
+        define_set_move_entry(),
+        define_set_result_entry(),
+        define_free_for_all_entry(),
+        define_free_for_all_commitment_entry(),
+        define_free_for_all_reveal_entry(),
+        define_free_for_all_ranking_entry()
+    ]
+
+    genesis: || { Ok(()) }
+
+    receive: |from, payload| {
+        handle_receive(from, payload)
+    }
+
+    functions: [
+        new_offer: {
+            inputs: |challenger_id_: Address, format_id_: String, reveal_deadline_: u64, protocol_: Protocol, arbiter_id_: Option<Address>, match_address_: Option<Address>, games_: Option<u64>|,
+            outputs: |result: ZomeApiResult<Address>|,
+            handler: handle_new_offer
+        }
+        new_commitment: {
+            inputs: |component_: Component, offer_address_: Address, host_id_: Address, nonce_: Option<String>, timelock_: Option<TimelockSetup>, hash_algorithm_: Option<HashAlgorithm>|,
+            outputs: |result: ZomeApiResult<Address>|,
+            handler: handle_new_commitment
+        }
+        new_move: {
+            inputs: |component_: Component, commitment_address_: Address, challenger_id_: Address|,
+            outputs: |result: ZomeApiResult<Address>|,
+            handler: handle_new_move
+        }
+        new_sealed_move: {
+            inputs: |component_: Component, commitment_address_: Address, challenger_id_: Address, nonce_: Option<String>|,
+            outputs: |result: ZomeApiResult<Address>|,
+            handler: handle_new_sealed_move
+        }
+        new_move_reveal: {
+            inputs: |reveal: Option<Reveal>, move_address: Address, component: Option<Component>|,
+            outputs: |result: ZomeApiResult<Address>|,
+            handler: handle_new_move_reveal
+        }
+        new_game_result: {
+            inputs: |move_address: Address, reveal: Option<Reveal>, component: Option<Component>|,
+            outputs: |result: ZomeApiResult<Address>|,
+            handler: handle_new_game_result
+        }
+        new_symmetric_game_result: {
+            inputs: |move_address: Address|,
+            outputs: |result: ZomeApiResult<Address>|,
+            handler: handle_new_symmetric_game_result
+        }
+        new_arbitrated_game_result: {
+            inputs: |move_address: Address|,
+            outputs: |result: ZomeApiResult<Address>|,
+            handler: handle_new_arbitrated_game_result
+        }
+        new_unsealed_game_result: {
+            inputs: |move_address: Address|,
+            outputs: |result: ZomeApiResult<Address>|,
+            handler: handle_new_unsealed_game_result
+        }
+        new_result_confirmation: {
+            inputs: |game_result_address: Address|,
+            outputs: |result: ZomeApiResult<Address>|,
+            handler: handle_new_result_confirmation
+        }
+        new_forfeit_claim: {
+            inputs: |move_address: Address|,
+            outputs: |result: ZomeApiResult<Address>|,
+            handler: handle_new_forfeit_claim
+        }
+        new_dispute: {
+            inputs: |subject_address: Address, reason: DisputeReason, referenced_addresses: Vec<Address>|,
+            outputs: |result: ZomeApiResult<Address>|,
+            handler: handle_new_dispute
+        }
+        new_jury: {
+            inputs: |dispute_address: Address|,
+            outputs: |result: ZomeApiResult<Address>|,
+            handler: handle_new_jury
+        }
+        new_vote_commitment: {
+            inputs: |jury_address: Address, vote: JuryVote|,
+            outputs: |result: ZomeApiResult<Address>|,
+            handler: handle_new_vote_commitment
+        }
+        new_vote_reveal: {
+            inputs: |vote_commitment_address: Address, vote: JuryVote|,
+            outputs: |result: ZomeApiResult<Address>|,
+            handler: handle_new_vote_reveal
+        }
+        new_jury_verdict: {
+            inputs: |dispute_address: Address|,
+            outputs: |result: ZomeApiResult<Address>|,
+            handler: handle_new_jury_verdict
+        }
+        new_match: {
+            inputs: |format_id: String, rule: MatchRule, participants: Vec<Address>, alternate_roles: Option<bool>, replay_draws: Option<bool>, component_limit: Option<u64>, constraints: Option<Vec<MoveConstraint>>|,
+            outputs: |result: ZomeApiResult<Address>|,
+            handler: handle_new_match
+        }
+        new_replay_offer: {
+            inputs: |move_address: Address|,
+            outputs: |result: ZomeApiResult<Address>|,
+            handler: handle_new_replay_offer
+        }
+        new_match_result: {
+            inputs: |match_address: Address|,
+            outputs: |result: ZomeApiResult<Address>|,
+            handler: handle_new_match_result
+        }
+        new_match_commitment: {
+            inputs: |match_address: Address, components: Vec<Component>, hash_algorithm_: Option<HashAlgorithm>|,
+            outputs: |result: ZomeApiResult<Address>|,
+            handler: handle_new_match_commitment
+        }
+        new_round_move: {
+            inputs: |component: Component, offer_address: Address|,
+            outputs: |result: ZomeApiResult<Address>|,
+            handler: handle_new_round_move
+        }
+        new_round_reveal: {
+            inputs: |move_address: Address|,
+            outputs: |result: ZomeApiResult<Address>|,
+            handler: handle_new_round_reveal
+        }
+        new_set_commitment: {
+            inputs: |components: Vec<Component>, offer_address: Address, hash_algorithm_: Option<HashAlgorithm>|,
+            outputs: |result: ZomeApiResult<Address>|,
+            handler: handle_new_set_commitment
+        }
+        new_set_move: {
+            inputs: |components: Vec<Component>, commitment_address: Address|,
+            outputs: |result: ZomeApiResult<Address>|,
+            handler: handle_new_set_move
+        }
+        new_set_result: {
+            inputs: |move_address: Address|,
+            outputs: |result: ZomeApiResult<Address>|,
+            handler: handle_new_set_result
+        }
+        new_free_for_all: {
+            inputs: |format_id: String, players: Vec<Address>|,
+            outputs: |result: ZomeApiResult<Address>|,
+            handler: handle_new_free_for_all
+        }
+        new_free_for_all_commitment: {
+            inputs: |game_address: Address, component: Component, nonce_: Option<String>, hash_algorithm_: Option<HashAlgorithm>|,
+            outputs: |result: ZomeApiResult<Address>|,
+            handler: handle_new_free_for_all_commitment
+        }
+        new_free_for_all_reveal: {
+            inputs: |commitment_address: Address|,
+            outputs: |result: ZomeApiResult<Address>|,
+            handler: handle_new_free_for_all_reveal
+        }
+        new_free_for_all_ranking: {
+            inputs: |game_address: Address|,
+            outputs: |result: ZomeApiResult<Address>|,
+            handler: handle_new_free_for_all_ranking
+        }
+        compute_commitment_hash: {
+            inputs: |reveal: Reveal, offer_address: Address, challenger_id: Address, hash_algorithm: HashAlgorithm|,
+            outputs: |result: ZomeApiResult<HashString>|,
+            handler: handle_compute_commitment_hash
+        }
+        get_offer: {
+            inputs: |address: Address|,
+            outputs: |result: ZomeApiResult<Offer>|,
+            handler: handle_get_offer
+        }
+        get_commitment: {
+            inputs: |address: Address|,
+            outputs: |result: ZomeApiResult<Commitment>|,
+            handler: handle_get_commitment
+        }
+        get_move: {
+            inputs: |address: Address|,
+            outputs: |result: ZomeApiResult<Move>|,
+            handler: handle_get_move
+        }
+        get_sealed_move: {
+            inputs: |address: Address|,
+            outputs: |result: ZomeApiResult<SealedMove>|,
+            handler: handle_get_sealed_move
+        }
+        get_move_reveal: {
+            inputs: |address: Address|,
+            outputs: |result: ZomeApiResult<MoveReveal>|,
+            handler: handle_get_move_reveal
+        }
+        get_game_result: {
+            inputs: |address: Address|,
+            outputs: |result: ZomeApiResult<GameResult>|,
+            handler: handle_get_game_result
+        }
+        get_result_confirmation: {
+            inputs: |address: Address|,
+            outputs: |result: ZomeApiResult<ResultConfirmation>|,
+            handler: handle_get_result_confirmation
+        }
+        get_result_status: {
+            inputs: |move_address: Address|,
+            outputs: |result: ZomeApiResult<Option<ResultStatus>>|,
+            handler: handle_get_result_status
+        }
+        get_forfeit_claim: {
+            inputs: |address: Address|,
+            outputs: |result: ZomeApiResult<ForfeitClaim>|,
+            handler: handle_get_forfeit_claim
+        }
+        get_outcome: {
+            inputs: |move_address: Address|,
+            outputs: |result: ZomeApiResult<Option<Outcome>>|,
+            handler: handle_get_outcome
+        }
+        get_rating: {
+            inputs: |agent_id: Address, format_id: String|,
+            outputs: |result: ZomeApiResult<Rating>|,
+            handler: handle_get_rating
+        }
+        get_rating_history: {
+            inputs: |agent_id: Address, format_id: String|,
+            outputs: |result: ZomeApiResult<Vec<RatingChange>>|,
+            handler: handle_get_rating_history
+        }
+        get_leaderboard: {
+            inputs: |format_id: String, page: u64, min_games: Option<u64>|,
+            outputs: |result: ZomeApiResult<Vec<LeaderboardEntry>>|,
+            handler: handle_get_leaderboard
+        }
+        new_rating_snapshot: {
+            inputs: |format_id: String|,
+            outputs: |result: ZomeApiResult<Address>|,
+            handler: handle_new_rating_snapshot
+        }
+        get_rating_snapshot: {
+            inputs: |address: Address|,
+            outputs: |result: ZomeApiResult<RatingSnapshot>|,
+            handler: handle_get_rating_snapshot
+        }
+        get_latest_rating_snapshot: {
+            inputs: |format_id: String|,
+            outputs: |result: ZomeApiResult<Option<Address>>|,
+            handler: handle_get_latest_rating_snapshot
+        }
+        get_match: {
+            inputs: |address: Address|,
+            outputs: |result: ZomeApiResult<Match>|,
+            handler: handle_get_match
+        }
+        get_match_result: {
+            inputs: |address: Address|,
+            outputs: |result: ZomeApiResult<MatchResult>|,
+            handler: handle_get_match_result
+        }
+        get_match_rounds: {
+            inputs: |match_address: Address|,
+            outputs: |result: ZomeApiResult<Vec<Address>>|,
+            handler: handle_get_match_rounds
+        }
+        get_match_commitment: {
+            inputs: |address: Address|,
+            outputs: |result: ZomeApiResult<MatchCommitment>|,
+            handler: handle_get_match_commitment
+        }
+        get_round_reveal: {
+            inputs: |address: Address|,
+            outputs: |result: ZomeApiResult<RoundReveal>|,
+            handler: handle_get_round_reveal
+        }
+        get_set_commitment: {
+            inputs: |address: Address|,
+            outputs: |result: ZomeApiResult<SetCommitment>|,
+            handler: handle_get_set_commitment
+        }
+        get_set_move: {
+            inputs: |address: Address|,
+            outputs: |result: ZomeApiResult<SetMove>|,
+            handler: handle_get_set_move
+        }
+        get_set_result: {
+            inputs: |address: Address|,
+            outputs: |result: ZomeApiResult<SetResult>|,
+            handler: handle_get_set_result
+        }
+        get_free_for_all: {
+            inputs: |address: Address|,
+            outputs: |result: ZomeApiResult<FreeForAll>|,
+            handler: handle_get_free_for_all
+        }
+        get_free_for_all_commitment: {
+            inputs: |address: Address|,
+            outputs: |result: ZomeApiResult<FreeForAllCommitment>|,
+            handler: handle_get_free_for_all_commitment
+        }
+        get_free_for_all_reveal: {
+            inputs: |address: Address|,
+            outputs: |result: ZomeApiResult<FreeForAllReveal>|,
+            handler: handle_get_free_for_all_reveal
+        }
+        get_free_for_all_ranking: {
+            inputs: |address: Address|,
+            outputs: |result: ZomeApiResult<FreeForAllRanking>|,
+            handler: handle_get_free_for_all_ranking
+        }
+        get_dispute: {
+            inputs: |address: Address|,
+            outputs: |result: ZomeApiResult<Dispute>|,
+            handler: handle_get_dispute
+        }
+        get_disputes: {
+            inputs: |address: Address|,
+            outputs: |result: ZomeApiResult<Vec<Address>>|,
+            handler: handle_get_disputes
+        }
+        is_game_disputed: {
+            inputs: |move_address: Address|,
+            outputs: |result: ZomeApiResult<bool>|,
+            handler: handle_is_game_disputed
+        }
+        get_dispute_verdict: {
+            inputs: |dispute_address: Address|,
+            outputs: |result: ZomeApiResult<Verdict>|,
+            handler: handle_get_dispute_verdict
+        }
+        get_jury: {
+            inputs: |address: Address|,
+            outputs: |result: ZomeApiResult<Jury>|,
+            handler: handle_get_jury
+        }
+        get_vote_commitment: {
+            inputs: |address: Address|,
+            outputs: |result: ZomeApiResult<VoteCommitment>|,
+            handler: handle_get_vote_commitment
+        }
+        get_vote_reveal: {
+            inputs: |address: Address|,
+            outputs: |result: ZomeApiResult<VoteReveal>|,
+            handler: handle_get_vote_reveal
+        }
+        get_jury_verdict: {
+            inputs: |address: Address|,
+            outputs: |result: ZomeApiResult<JuryVerdict>|,
+            handler: handle_get_jury_verdict
+        }
+        /*
+        get_agent_id: {
+            inputs: |address: Address|,
+            outputs: |result: ZomeApiResult<AgentId>|,
+            handler: handle_get_agent_id
+        }
+        */
+    ]
+
+    traits: {
+        hc_public [
+            new_offer,
+            get_offer,
+            new_commitment,
+            get_commitment,
+            new_move,
+            get_move,
+            new_sealed_move,
+            get_sealed_move,
+            new_move_reveal,
+            get_move_reveal,
+            new_game_result,
+            new_symmetric_game_result,
+            new_arbitrated_game_result,
+            new_unsealed_game_result,
+            get_game_result,
+            new_result_confirmation,
+            get_result_confirmation,
+            get_result_status,
+            new_forfeit_claim,
+            get_forfeit_claim,
+            get_outcome,
+            get_rating,
+            get_rating_history,
+            get_leaderboard,
+            new_match,
+            get_match,
+            get_match_rounds,
+            new_replay_offer,
+            new_match_result,
+            get_match_result,
+            new_match_commitment,
+            get_match_commitment,
+            new_round_move,
+            new_round_reveal,
+            get_round_reveal,
+            new_set_commitment,
+            get_set_commitment,
+            new_set_move,
+            get_set_move,
+            new_set_result,
+            get_set_result,
+            new_free_for_all,
+            get_free_for_all,
+            new_free_for_all_commitment,
+            get_free_for_all_commitment,
+            new_free_for_all_reveal,
+            get_free_for_all_reveal,
+            new_free_for_all_ranking,
+            get_free_for_all_ranking,
+            new_rating_snapshot,
+            get_rating_snapshot,
+            get_latest_rating_snapshot,
+            new_dispute,
+            get_dispute,
+            get_disputes,
+            is_game_disputed,
+            get_dispute_verdict,
+            new_jury,
+            get_jury,
+            new_vote_commitment,
+            get_vote_commitment,
+            new_vote_reveal,
+            get_vote_reveal,
+            new_jury_verdict,
+            get_jury_verdict,
+            compute_commitment_hash
+            // get_agent_id
+        ]
+    }
+}
+
+// Private helper functions
+
+// The base58 multihash of a canonical preimage
+fn calculate_hash(preimage: &[u8], hash_algorithm: &HashAlgorithm) -> ZomeApiResult<HashString> {
+    let multihash: Vec<u8> = match hash_algorithm {
+        HashAlgorithm::Sha2256 => encode(Multihash::SHA2256, preimage).map_err(|_| String::from("Could not hash preimage"))?,
+        HashAlgorithm::Sha3256 => encode(Multihash::SHA3256, preimage).map_err(|_| String::from("Could not hash preimage"))?,
+        HashAlgorithm::Blake2b256 => {
+            // multihash 0.8 has no blake2b-256, so build the multihash by hand: varint code 0xb220, length, digest
+            let mut hasher = match VarBlake2b::new(32) {
+                Ok(hasher) => hasher,
+                Err(_) => return Err(String::from("Could not create blake2b hasher").into()),
+            };
+            hasher.input(preimage);
+            let mut multihash: Vec<u8> = vec![0xa0, 0xe4, 0x02, 32];
+            multihash.extend(hasher.vec_result());
+            multihash
+        },
+    };
+    Ok(HashString::from(multihash.to_base58()))
+}
+
+// What a commitment hash is computed over, see "Canonical encoding" in the README.
+// Binding the offer and challenger means a hash copied into another game cannot be revealed there.
+fn commitment_preimage(reveal: &Reveal, offer_address: &Address, challenger_id: &Address) -> Vec<u8> {
+    canonical::Encoder::new("roshambo-reveal-v2")
+        .string(&offer_address.to_string())
+        .string(&challenger_id.to_string())
+        .string(&reveal.component.name)
+        .list(&reveal.component.wins_against)
+        .list(&reveal.component.loses_against)
+        .string(&reveal.nonce)
+        .finish()
+}
+
+// The host's sealed reveal in the symmetric protocol, bound to the host as well so it can't copy the challenger's hash
+fn host_preimage(reveal: &Reveal, offer_address: &Address, challenger_id: &Address, host_id: &Address) -> Vec<u8> {
+    canonical::Encoder::new("roshambo-host-reveal-v1")
+        .string(&offer_address.to_string())
+        .string(&challenger_id.to_string())
+        .string(&host_id.to_string())
+        .string(&reveal.component.name)
+        .list(&reveal.component.wins_against)
+        .list(&reveal.component.loses_against)
+        .string(&reveal.nonce)
+        .finish()
+}
+
+// A leaf of a match commitment, bound to the match, round and challenger as commitment_preimage binds the offer
+fn round_leaf_preimage(reveal: &Reveal, match_address: &Address, round: u64, challenger_id: &Address) -> Vec<u8> {
+    canonical::Encoder::new("roshambo-round-reveal-v1")
+        .string(&match_address.to_string())
+        .string(&round.to_string())
+        .string(&challenger_id.to_string())
+        .string(&reveal.component.name)
+        .list(&reveal.component.wins_against)
+        .list(&reveal.component.loses_against)
+        .string(&reveal.nonce)
+        .finish()
+}
+
+fn round_leaves(reveals: &[Reveal], match_address: &Address, challenger_id: &Address, hash_algorithm: &HashAlgorithm) -> ZomeApiResult<Vec<HashString>> {
+    let mut leaves: Vec<HashString> = Vec::new();
+    for (index, reveal) in reveals.iter().enumerate() {
+        leaves.push(calculate_hash(&round_leaf_preimage(reveal, match_address, index as u64 + 1, challenger_id), hash_algorithm)?);
+    }
+    Ok(leaves)
+}
+
+// One game's hash in a set commitment, bound to the game's position in the set as well as the offer and challenger
+fn set_preimage(reveal: &Reveal, offer_address: &Address, challenger_id: &Address, game: u64) -> Vec<u8> {
+    canonical::Encoder::new("roshambo-set-reveal-v1")
+        .string(&offer_address.to_string())
+        .string(&challenger_id.to_string())
+        .string(&game.to_string())
+        .string(&reveal.component.name)
+        .list(&reveal.component.wins_against)
+        .list(&reveal.component.loses_against)
+        .string(&reveal.nonce)
+        .finish()
+}
+
+// A free-for-all commitment hash, bound to the game and the committing player
+fn free_for_all_preimage(reveal: &Reveal, game_address: &Address, player: &Address) -> Vec<u8> {
+    canonical::Encoder::new("roshambo-free-for-all-reveal-v1")
+        .string(&game_address.to_string())
+        .string(&player.to_string())
+        .string(&reveal.component.name)
+        .list(&reveal.component.wins_against)
+        .list(&reveal.component.loses_against)
+        .string(&reveal.nonce)
+        .finish()
+}
+
+fn merkle_node(left: &HashString, right: &HashString, hash_algorithm: &HashAlgorithm) -> ZomeApiResult<HashString> {
+    let preimage: Vec<u8> = canonical::Encoder::new("roshambo-merkle-node-v1")
+        .string(&left.to_string())
+        .string(&right.to_string())
+        .finish();
+    calculate_hash(&preimage, hash_algorithm)
+}
+
+fn allowed_hash_algorithms(format_id: &str) -> Vec<HashAlgorithm> {
+    let all: Vec<HashAlgorithm> = vec![HashAlgorithm::Sha2256, HashAlgorithm::Sha3256, HashAlgorithm::Blake2b256];
+    let policy: HashAlgorithmPolicy = match hdk::property("hash_algorithms")
+        .ok()
+        .and_then(|property| serde_json::from_str(&String::from(property)).ok()) {
+        Some(policy) => policy,
+        None => return all,
+    };
+    if let Some(algorithms) = policy.formats.and_then(|mut formats| formats.remove(format_id)) {
+        return algorithms;
+    }
+    policy.allowed.unwrap_or(all)
+}
+
+// The requested hash algorithm if the format allows it, otherwise the format's first allowed one
+fn choose_hash_algorithm(format_id: &str, hash_algorithm: Option<HashAlgorithm>) -> ZomeApiResult<HashAlgorithm> {
+    let allowed: Vec<HashAlgorithm> = allowed_hash_algorithms(format_id);
+    let hash_algorithm: HashAlgorithm = match hash_algorithm {
+        Some(hash_algorithm) => hash_algorithm,
+        None => match allowed.first() {
+            Some(hash_algorithm) => hash_algorithm.clone(),
+            None => return Err(String::from("No hash algorithm is allowed").into()),
+        },
+    };
+    if !allowed.contains(&hash_algorithm) {
+        return Err(String::from("Hash algorithm is not allowed").into());
+    }
+    Ok(hash_algorithm)
+}
+
+fn get_author(entry_address: &Address) -> ZomeApiResult<Address> {
+    // this is not the correct way of handling agent ids
+    // let agent_id: AgentId = handle_get_agent_id(author_address)?;
+    author_from_header(&get_header(entry_address)?)
+}
+
+fn get_header(entry_address: &Address) -> ZomeApiResult<ChainHeader> {
+    if let GetEntryResultType::Single(result) = hdk::get_entry_result(
+        entry_address,
+        GetEntryOptions {
+            entry: false,
+            headers: true,
+            ..Default::default()
+        },
+    )?
+    .result
+    {
+        match result.headers.into_iter().next() {
+            Some(header) => Ok(header),
+            None => Err(String::from("No header found").into()),
+        }
+    } else {
+        Err(String::from("Entry not found").into())
+    }
+}
+
+// Seconds since the unix epoch of a header timestamp, e.g. "2019-04-10T12:30:00+00:00"
+fn timestamp_seconds(chain_header: &ChainHeader) -> ZomeApiResult<u64> {
+    let timestamp: String = chain_header.timestamp().to_string();
+    let field = |start: usize, end: usize| -> ZomeApiResult<i64> {
+        match timestamp.get(start..end).and_then(|digits| digits.parse::<i64>().ok()) {
+            Some(value) => Ok(value),
+            None => Err(format!("Malformed header timestamp: {}", timestamp).into()),
+        }
+    };
+    let (year, month, day) = (field(0, 4)?, field(5, 7)?, field(8, 10)?);
+    let (hour, minute, second) = (field(11, 13)?, field(14, 16)?, field(17, 19)?);
+
+    // skip fractional seconds, then apply the utc offset if there is one
+    let zone: String = timestamp.chars().skip(19).skip_while(|c| *c == '.' || c.is_digit(10)).collect();
+    let offset: i64 = match zone.chars().next() {
+        Some(sign) if sign == '+' || sign == '-' => {
+            let hours = zone.get(1..3).and_then(|digits| digits.parse::<i64>().ok()).unwrap_or(0);
+            let minutes = zone.get(4..6).and_then(|digits| digits.parse::<i64>().ok()).unwrap_or(0);
+            let offset = hours * 3600 + minutes * 60;
+            if sign == '+' { offset } else { -offset }
+        },
+        _ => 0,
+    };
+
+    // days from civil date, see http://howardhinnant.github.io/date_algorithms.html
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = (if year >= 0 { year } else { year - 399 }) / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146097 + day_of_era - 719468;
+
+    let seconds = days * 86400 + hour * 3600 + minute * 60 + second - offset;
+    if seconds < 0 {
+        return Err(format!("Header timestamp before epoch: {}", timestamp).into());
+    }
+    Ok(seconds as u64)
+}
+
+fn author_from_header(chain_header: &ChainHeader) -> ZomeApiResult<Address> {
+    match chain_header.provenances().first() {
+        Some(provenance) => Ok(provenance.clone().source()),
+        None => Err(String::from("Header has no author").into()),
+    }
+}
+
+// The game played on a move, whichever protocol it uses
+struct Game {
+    offer: Offer,
+    offer_address: Address,
+    commitment_address: Address,
+    host_id: Address,
+    challenger_id: Address,
+    challenger_hash: HashString,
+    host_hash: Option<HashString>,         // symmetric protocol only
+    host_component: Option<Component>,     // asymmetric protocol only
+    format_id: String,
+    timelock: Option<TimelockPuzzle>,
+    hash_algorithm: HashAlgorithm,
+    match_committed: bool, // the challenger_hash is the root of a match commitment
+}
+
+fn get_game(move_address: &Address) -> ZomeApiResult<Game> {
+    let host_id: Address = get_author(move_address)?;
+    let (commitment_address, challenger_id, challenger_hash, host_hash, host_component, format_id, round_offer_address) = match hdk::get_entry(move_address) {
+        Ok(Some(Entry::App(entry_type, api_result))) => match String::from(entry_type).as_ref() {
+            "move" => {
+                let move_: Move = api_result.try_into()?;
+                (move_.commitment_address, move_.challenger_id, move_.hash, None, Some(move_.component), move_.format_id, move_.offer_address)
+            },
+            "sealed_move" => {
+                let sealed_move: SealedMove = api_result.try_into()?;
+                (sealed_move.commitment_address, sealed_move.challenger_id, sealed_move.hash, Some(sealed_move.host_hash), None, sealed_move.format_id, None)
+            },
+            _ => return Err(String::from("No move found").into()),
+        },
+        _ => return Err(String::from("No move found").into()),
+    };
+    let (offer_address, timelock, hash_algorithm, match_committed) = match round_offer_address {
+        Some(offer_address) => {
+            let match_commitment: MatchCommitment = handle_get_match_commitment(commitment_address.clone())?;
+            (offer_address, None, match_commitment.hash_algorithm, true)
+        },
+        None => {
+            let commitment: Commitment = handle_get_commitment(commitment_address.clone())?;
+            (commitment.offer_address, commitment.timelock, commitment.hash_algorithm, false)
+        },
+    };
+    let offer: Offer = handle_get_offer(offer_address.clone())?;
+
+    Ok(Game {
+        offer,
+        offer_address,
+        commitment_address,
+        host_id,
+        challenger_id,
+        challenger_hash,
+        host_hash,
+        host_component,
+        format_id,
+        timelock,
+        hash_algorithm,
+        match_committed,
+    })
+}
+
+// (author, move reveal) for each reveal published on a sealed move
+fn get_move_reveals(move_address: &Address) -> ZomeApiResult<Vec<(Address, MoveReveal)>> {
+    let mut move_reveals: Vec<(Address, MoveReveal)> = Vec::new();
+    for address in hdk::get_links(move_address, "move_reveal")?.addresses().iter() {
+        move_reveals.push((get_author(address)?, handle_get_move_reveal(address.clone())?));
+    }
+    Ok(move_reveals)
+}
+
+// (address, outcome) of the entry that decides the game, settled at read time rather than in validation
+// since a game result and a forfeit claim can both be valid when they are committed concurrently.
+// A game result proves the game was played, so it decides the game over any forfeit claim;
+// among several candidates of a kind the lowest address wins, so every agent picks the same one.
+fn get_decided_outcome(move_address: &Address) -> ZomeApiResult<Option<(Address, Outcome)>> {
+    let mut game_results: Vec<(String, Address, Outcome)> = Vec::new();
+    for (address, game_result) in get_game_results(move_address)? {
+        if !is_overturned(&address)? {
+            game_results.push((String::from(address.clone()), address, game_result.into()));
+        }
+    }
+    let mut claims: Vec<(String, Address, Outcome)> = Vec::new();
+    for (address, claim) in get_forfeit_claims(move_address)? {
+        if !is_overturned(&address)? {
+            claims.push((String::from(address.clone()), address, claim.into()));
+        }
+    }
+    let candidates: Vec<(String, Address, Outcome)> = if game_results.is_empty() { claims } else { game_results };
+    Ok(candidates
+        .into_iter()
+        .min_by(|(key, _, _), (other_key, _, _)| key.cmp(other_key))
+        .map(|(_, address, outcome)| (address, outcome)))
+}
+
+// (address, game result) for each result linked to the move that is about this move
+fn get_game_results(move_address: &Address) -> ZomeApiResult<Vec<(Address, GameResult)>> {
+    let mut game_results: Vec<(Address, GameResult)> = Vec::new();
+    for address in hdk::get_links(move_address, "game_result")?.addresses().iter() {
+        let game_result: GameResult = handle_get_game_result(address.clone())?;
+        if game_result.move_address() == move_address {
+            game_results.push((address.clone(), game_result));
+        }
+    }
+    Ok(game_results)
+}
+
+// (address, forfeit claim) for each claim linked to the move that is about this move
+fn get_forfeit_claims(move_address: &Address) -> ZomeApiResult<Vec<(Address, ForfeitClaim)>> {
+    let mut claims: Vec<(Address, ForfeitClaim)> = Vec::new();
+    for address in hdk::get_links(move_address, "forfeit_claim")?.addresses().iter() {
+        let claim: ForfeitClaim = handle_get_forfeit_claim(address.clone())?;
+        if claim.move_address == *move_address {
+            claims.push((address.clone(), claim));
+        }
+    }
+    Ok(claims)
+}
+
+fn validate_offer_move_link(validation_data: hdk::LinkValidationData) -> Result<(), String> {
+    if let hdk::LinkValidationData::LinkAdd{link, validation_data: validation_} = validation_data {
+        let link_author_address: Address = author_from_header(&validation_.package.chain_header)?;
+        let target: &Address = link.link().target();
+        let offer_address: Address = match hdk::get_entry(target) {
+            Ok(Some(Entry::App(entry_type, api_result))) => match String::from(entry_type).as_ref() {
+                "set_move" => {
+                    let set_move: SetMove = api_result.try_into()?;
+                    handle_get_set_commitment(set_move.commitment_address)?.offer_address
+                },
+                _ => get_game(target)?.offer_address,
+            },
+            _ => return Err(String::from("Link target not found")),
+        };
+        if offer_address != *link.link().base() {
+            return Err(String::from("Move answers another offer"));
+        }
+        if get_author(target)? != link_author_address {
+            return Err(String::from("Link author is not the move's author."));
+        }
+    }
+    Ok(())
+}
+
+// A game result or forfeit claim may only be linked to the move it is about, by its own author.
+// Its author was checked when the entry itself was validated.
+fn validate_move_link(validation_data: hdk::LinkValidationData) -> Result<(), String> {
+    if let hdk::LinkValidationData::LinkAdd{link, validation_data: validation_} = validation_data {
+        let link_author_address: Address = author_from_header(&validation_.package.chain_header)?;
+        let base: &Address = link.link().base();
+        let target: &Address = link.link().target();
+        let move_address: Address = match hdk::get_entry(target) {
+            Ok(Some(Entry::App(entry_type, api_result))) => match String::from(entry_type).as_ref() {
+                "game_result" => {
+                    let game_result: GameResult = api_result.try_into()?;
+                    game_result.move_address().clone()
+                },
+                "forfeit_claim" => {
+                    let claim: ForfeitClaim = api_result.try_into()?;
+                    claim.move_address
+                },
+                "round_reveal" => {
+                    let round_reveal: RoundReveal = api_result.try_into()?;
+                    round_reveal.move_address
+                },
+                _ => return Err(String::from("Link target is not about a move")),
+            },
+            _ => return Err(String::from("Link target not found")),
+        };
+        if move_address != *base {
+            return Err(String::from("Link target is about another move"));
+        }
+        if get_author(target)? != link_author_address {
+            return Err(String::from("Link author is not the target's author."));
+        }
+    }
+    Ok(())
+}
+
+// A dispute may only be linked from its subject, by the player who raised it
+fn validate_dispute_link(validation_data: hdk::LinkValidationData) -> Result<(), String> {
+    if let hdk::LinkValidationData::LinkAdd{link, validation_data: validation_} = validation_data {
+        let link_author_address: Address = author_from_header(&validation_.package.chain_header)?;
+        let dispute: Dispute = handle_get_dispute(link.link().target().clone())?;
+        if dispute.subject_address != *link.link().base() {
+            return Err(String::from("Dispute is against another entry"));
+        }
+        if get_author(link.link().target())? != link_author_address {
+            return Err(String::from("Link author is not the dispute's author."));
+        }
+    }
+    Ok(())
+}
+
+// The host's published reveal in the symmetric protocol, None in the asymmetric protocol
+fn get_host_reveal(game: &Game, move_address: &Address) -> ZomeApiResult<Option<Reveal>> {
+    match game.offer.protocol {
+        Protocol::Asymmetric => Ok(None),
+        Protocol::Symmetric => match get_move_reveals(move_address)?
+            .into_iter()
+            .find(|(author, _)| *author == game.host_id) {
+            Some((_, move_reveal)) => Ok(Some(move_reveal.reveal)),
+            None => Err(String::from("Host has not revealed yet").into()),
+        },
+    }
+}
+
+fn seal_reveal(reveal: &Reveal, setup: TimelockSetup) -> ZomeApiResult<TimelockPuzzle> {
+    let key = timelock::fast_key(
+        &timelock::parse_number(&setup.modulus)?,
+        &timelock::parse_number(&setup.totient)?,
+        &timelock::parse_number(&setup.base)?,
+        setup.squarings,
+    )?;
+    let plaintext: String = JsonString::from(reveal.clone()).into();
+    let sealed: Vec<u8> = timelock::apply_key(&key, plaintext.as_bytes())?;
+
+    Ok(TimelockPuzzle {
+        modulus: setup.modulus,
+        base: setup.base,
+        squarings: setup.squarings,
+        sealed_reveal: canonical::to_hex(&sealed),
+    })
+}
+
+fn unseal_reveal(puzzle: &TimelockPuzzle, puzzle_solution: &str) -> ZomeApiResult<Reveal> {
+    let key = timelock::parse_number(puzzle_solution)?;
+    let unsealed: Vec<u8> = timelock::apply_key(&key, &canonical::from_hex(&puzzle.sealed_reveal)?)?;
+    match String::from_utf8(unsealed) {
+        Ok(plaintext) => Ok(JsonString::from(plaintext).try_into()?),
+        Err(_) => Err(String::from("Puzzle solution does not unseal the reveal").into()),
+    }
+}
+
+fn commit_reveal_secret(reveal: Reveal, commitment_address: Address) -> ZomeApiResult<Address> {
+    let reveal_secret = RevealSecret {
+        reveal: reveal,
+        commitment_address: commitment_address,
+    };
+
+    let entry = Entry::App("reveal_secret".into(), reveal_secret.into());
+    hdk::commit_entry(&entry)
+}
+
+fn get_reveal_secrets() -> ZomeApiResult<Vec<RevealSecret>> {
+    let mut reveal_secrets: Vec<RevealSecret> = Vec::new();
+    for address in hdk::query("reveal_secret".into(), 0, 0)? {
+        if let Ok(Some(Entry::App(_, api_result))) = hdk::get_entry(&address) {
+            reveal_secrets.push(api_result.try_into()?);
+        }
+    }
+    Ok(reveal_secrets)
+}
+
+fn get_reveal_secret(commitment_address: &Address) -> ZomeApiResult<Reveal> {
+    match get_reveal_secrets()?
+        .into_iter()
+        .find(|reveal_secret| reveal_secret.commitment_address == *commitment_address) {
+        Some(reveal_secret) => Ok(reveal_secret.reveal),
+        None => Err(String::from("No reveal secret found").into()),
+    }
+}
+
+// This agent's kept reveals for a commitment whose nonces it derived from nonce_address, one per nonce counter
+fn get_derived_reveals(commitment_address: &Address, nonce_address: &Address, nonce_counters: &[u64]) -> ZomeApiResult<Vec<Reveal>> {
+    let reveal_secrets: Vec<RevealSecret> = get_reveal_secrets()?
+        .into_iter()
+        .filter(|reveal_secret| reveal_secret.commitment_address == *commitment_address)
+        .collect();
+    let mut reveals: Vec<Reveal> = Vec::new();
+    for nonce_counter in nonce_counters {
+        let nonce: String = derive_nonce(nonce_address, *nonce_counter)?;
+        match reveal_secrets.iter().find(|reveal_secret| reveal_secret.reveal.nonce == nonce) {
+            Some(reveal_secret) => reveals.push(reveal_secret.reveal.clone()),
+            None => return Err(String::from("No reveal secret found").into()),
+        }
+    }
+    Ok(reveals)
+}
+
+// The reveal behind a hash this agent committed (a commitment or sealed move): from the agent's
+// reveal secret if there is one, otherwise from the component with its derived nonce re-derived
+fn recover_reveal(commitment_address: &Address, component: Option<Component>) -> ZomeApiResult<Reveal> {
+    if let Ok(reveal) = get_reveal_secret(commitment_address) {
+        return Ok(reveal);
+    }
+    let component: Component = match component {
+        Some(component) => component,
+        None => return Err(String::from("No reveal secret found").into()),
+    };
+    let (offer_address, nonce_counter) = match hdk::get_entry(commitment_address) {
+        Ok(Some(Entry::App(entry_type, api_result))) => match String::from(entry_type).as_ref() {
+            "commitment" => {
+                let commitment: Commitment = api_result.try_into()?;
+                (commitment.offer_address, commitment.nonce_counter)
+            },
+            "sealed_move" => {
+                let sealed_move: SealedMove = api_result.try_into()?;
+                (handle_get_commitment(sealed_move.commitment_address)?.offer_address, sealed_move.nonce_counter)
+            },
+            _ => return Err(String::from("No commitment found").into()),
+        },
+        _ => return Err(String::from("No commitment found").into()),
+    };
+    match nonce_counter {
+        Some(nonce_counter) => Ok(Reveal { component: component, nonce: derive_nonce(&offer_address, nonce_counter)? }),
+        None => Err(String::from("No reveal secret found and nonce was not derived").into()),
+    }
+}
+
+// What the author of a commitment, move or sealed move signs (everything but the signature),
+// so a transcript of the game can be checked without trusting the node that served it
+fn commitment_content(commitment: &Commitment) -> Vec<u8> {
+    let timelock: Vec<String> = match &commitment.timelock {
+        Some(puzzle) => vec![
+            puzzle.modulus.clone(),
+            puzzle.base.clone(),
+            puzzle.squarings.to_string(),
+            puzzle.sealed_reveal.clone(),
+        ],
+        None => Vec::new(),
+    };
+    canonical::Encoder::new("roshambo-commitment-v1")
+        .string(&commitment.hash.to_string())
+        .string(commitment.hash_algorithm.name())
+        .string(&commitment.offer_address.to_string())
+        .string(&commitment.host_id.to_string())
+        .string(&commitment.format_id)
+        .list(&timelock)
+        .optional(commitment.nonce_counter.map(|nonce_counter| nonce_counter.to_string()))
+        .finish()
+}
+
+fn move_content(move_: &Move) -> Vec<u8> {
+    canonical::Encoder::new("roshambo-move-v2")
+        .string(&move_.component.name)
+        .list(&move_.component.wins_against)
+        .list(&move_.component.loses_against)
+        .string(&move_.commitment_address.to_string())
+        .string(&move_.challenger_id.to_string())
+        .string(&move_.hash.to_string())
+        .string(&move_.format_id)
+        .optional(move_.offer_address.as_ref().map(|offer_address| offer_address.to_string()))
+        .finish()
+}
+
+fn set_commitment_content(set_commitment: &SetCommitment) -> Vec<u8> {
+    let hashes: Vec<String> = set_commitment.hashes.iter().map(|hash| hash.to_string()).collect();
+    canonical::Encoder::new("roshambo-set-commitment-v1")
+        .list(&hashes)
+        .string(set_commitment.hash_algorithm.name())
+        .string(&set_commitment.offer_address.to_string())
+        .string(&set_commitment.host_id.to_string())
+        .string(&set_commitment.format_id)
+        .finish()
+}
+
+fn set_move_content(set_move: &SetMove) -> Vec<u8> {
+    set_move.components
+        .iter()
+        .fold(
+            canonical::Encoder::new("roshambo-set-move-v1").string(&set_move.components.len().to_string()),
+            |encoder, component| encoder.string(&component.name).list(&component.wins_against).list(&component.loses_against),
+        )
+        .string(&set_move.commitment_address.to_string())
+        .string(&set_move.challenger_id.to_string())
+        .string(&set_move.format_id)
+        .finish()
+}
+
+fn free_for_all_commitment_content(commitment: &FreeForAllCommitment) -> Vec<u8> {
+    canonical::Encoder::new("roshambo-free-for-all-commitment-v1")
+        .string(&commitment.game_address.to_string())
+        .string(&commitment.hash.to_string())
+        .string(commitment.hash_algorithm.name())
+        .finish()
+}
+
+fn match_commitment_content(match_commitment: &MatchCommitment) -> Vec<u8> {
+    canonical::Encoder::new("roshambo-match-commitment-v1")
+        .string(&match_commitment.match_address.to_string())
+        .string(&match_commitment.root.to_string())
+        .string(match_commitment.hash_algorithm.name())
+        .string(&match_commitment.rounds.to_string())
+        .finish()
+}
+
+fn sealed_move_content(sealed_move: &SealedMove) -> Vec<u8> {
+    canonical::Encoder::new("roshambo-sealed-move-v1")
+        .string(&sealed_move.host_hash.to_string())
+        .string(&sealed_move.commitment_address.to_string())
+        .string(&sealed_move.challenger_id.to_string())
+        .string(&sealed_move.hash.to_string())
+        .string(&sealed_move.format_id)
+        .optional(sealed_move.nonce_counter.map(|nonce_counter| nonce_counter.to_string()))
+        .finish()
+}
+
+fn confirmation_content(game_result_address: &Address) -> Vec<u8> {
+    canonical::Encoder::new("roshambo-result-confirmation-v1")
+        .string(&game_result_address.to_string())
+        .finish()
+}
+
+fn sign_content(content: &[u8]) -> ZomeApiResult<String> {
+    hdk::sign(canonical::to_hex(content))
+}
+
+fn verify_content(author: &Address, signature: &str, content: &[u8]) -> Result<(), String> {
+    let provenance = Provenance::new(author.clone(), Signature::from(signature.to_string()));
+    if !hdk::verify_signature(provenance, canonical::to_hex(content))? {
+        return Err(String::from("Signature does not match content"));
+    }
+    Ok(())
+}
+
+// Refuses weak nonces, and nonces this agent has committed with before
+fn check_new_nonce(nonce: &str) -> ZomeApiResult<()> {
+    validate_nonce(nonce)?;
+    if get_reveal_secrets()?.iter().any(|reveal_secret| reveal_secret.reveal.nonce == nonce) {
+        return Err(String::from("Nonce has already been used").into());
+    }
+    Ok(())
+}
+
+fn get_escrowed_reveal(commitment_address: &Address) -> ZomeApiResult<Reveal> {
+    for address in hdk::query("escrowed_reveal".into(), 0, 0)? {
+        if let Ok(Some(Entry::App(_, api_result))) = hdk::get_entry(&address) {
+            let escrowed_reveal: EscrowedReveal = api_result.try_into()?;
+            if escrowed_reveal.commitment_address == *commitment_address {
+                return Ok(escrowed_reveal.reveal);
+            }
+        }
+    }
+    Err(String::from("No escrowed reveal found").into())
+}
+
+fn commit_game_result(game_result: GameResult, game: &Game, move_address: &Address) -> ZomeApiResult<Address> {
+    let entry = Entry::App("game_result".into(), game_result.into());
+    let address = hdk::commit_entry(&entry)?;
+    hdk::link_entries(move_address, &address, "game_result")?;
+    index_outcome(&game.format_id, &address)?;
+    Ok(address)
+}
+
+fn create_game_result(reveal_: Reveal, move_address_: Address, host_id: Address, host_reveal_: Option<Reveal>, puzzle_solution_: Option<String>) -> ZomeApiResult<GameResult> {
+    let game: Game = get_game(&move_address_)?;
+    let host_component: Component = match (game.host_component, host_reveal_.clone()) {
+        (Some(component), _) => component,
+        (None, Some(host_reveal)) => host_reveal.component,
+        (None, None) => return Err(String::from("No host reveal for sealed move").into()),
+    };
+    let challenger_component: &Component = &reveal_.component;
+    let format_id_ = game.format_id;
+    let challenger_id = game.challenger_id;
+    let winner: String = resolve_components(&host_component, challenger_component);
+
+    if winner == String::from("host") {
+        Ok(GameResult::Win {
+            reveal: reveal_,
+            move_address: move_address_,
+            winner_id: host_id,
+            loser_id: challenger_id,
+            format_id: format_id_,
+            host_reveal: host_reveal_,
+            puzzle_solution: puzzle_solution_,
+        })
+    } else if winner == String::from("challenger") {
+        Ok(GameResult::Win {
+            reveal: reveal_,
+            move_address: move_address_,
+            winner_id: challenger_id,
+            loser_id: host_id,
+            format_id: format_id_,
+            host_reveal: host_reveal_,
+            puzzle_solution: puzzle_solution_,
+        })
+    } else if winner == String::from("draw") {
+        Ok(GameResult::Draw {
+            reveal: reveal_,
+            move_address: move_address_,
+            players: vec![host_id, challenger_id],
+            format_id: format_id_,
+            host_reveal: host_reveal_,
+            puzzle_solution: puzzle_solution_,
+        })
+    } else {
+        Err(String::from("Components do not resolve to a winner").into())
+    }
+}
+
+fn create_set_result(set_move: &SetMove, move_address: &Address, reveals: Vec<Reveal>) -> ZomeApiResult<SetResult> {
+    let host_id: Address = get_author(move_address)?;
+    if reveals.len() != set_move.components.len() {
+        return Err(String::from("Set result needs one reveal per game").into());
+    }
+    let winners: Vec<Option<Address>> = set_move.components
+        .iter()
+        .zip(reveals.iter())
+        .map(|(host_component, reveal)| match resolve_components(host_component, &reveal.component).as_ref() {
+            "host" => Some(host_id.clone()),
+            "challenger" => Some(set_move.challenger_id.clone()),
+            _ => None,
+        })
+        .collect();
+
+    Ok(SetResult {
+        move_address: move_address.clone(),
+        reveals: reveals,
+        winners: winners,
+        format_id: set_move.format_id.clone(),
+    })
+}
+
+// resolve_components over every pair of a set: each component scores a point for each other component it beats
+fn score_components(components: &[Component]) -> Vec<u64> {
+    let mut scores: Vec<u64> = vec![0; components.len()];
+    for (index, component) in components.iter().enumerate() {
+        for (other_index, other_component) in components.iter().enumerate().skip(index + 1) {
+            match resolve_components(component, other_component).as_ref() {
+                "host" => scores[index] += 1,
+                "challenger" => scores[other_index] += 1,
+                _ => (),
+            }
+        }
+    }
+    scores
+}
+
+// Competition ranking: a player's rank is the number of players who scored more
+fn rank_scores(scores: &[u64]) -> Vec<u64> {
+    scores
+        .iter()
+        .map(|score| scores.iter().filter(|other_score| *other_score > score).count() as u64)
+        .collect()
+}
+
+fn create_free_for_all_ranking(game_address: &Address, reveal_addresses: Vec<Address>) -> ZomeApiResult<FreeForAllRanking> {
+    let mut components: Vec<Component> = Vec::new();
+    for address in reveal_addresses.iter() {
+        components.push(handle_get_free_for_all_reveal(address.clone())?.reveal.component);
+    }
+    let scores: Vec<u64> = score_components(&components);
+
+    Ok(FreeForAllRanking {
+        game_address: game_address.clone(),
+        reveal_addresses: reveal_addresses,
+        ranks: rank_scores(&scores),
+        scores: scores,
+    })
+}
+
+fn resolve_components(host_component: &Component, challenger_component: &Component) -> String {
+    if host_component.wins_against.contains(&challenger_component.name) {
+        return String::from("host");
+    }
+    if challenger_component.wins_against.contains(&host_component.name) {
+        return String::from("challenger");
+    }
+    if host_component.loses_against.contains(&challenger_component.name) {
+        return String::from("challenger");
+    }
+    if challenger_component.loses_against.contains(&host_component.name) {
+        return String::from("host");
+    }
+    return String::from("draw");
+}
+
+fn validate_offer(offer: Offer, offer_header: &ChainHeader) -> Result<(), String> {
+    let offer_author_address: Address = author_from_header(offer_header)?;
+    if let Some(games) = offer.games {
+        if games == 0 {
+            return Err(String::from("A game set needs at least one game"));
+        }
+        if offer.match_address.is_some() || offer.arbiter_id.is_some() || offer.protocol != Protocol::Asymmetric {
+            return Err(String::from("A game set uses the asymmetric protocol, without a match or an arbiter"));
+        }
+    }
+    if offer.match_address.is_some() != offer.round.is_some() || (offer.match_address.is_none() && offer.previous_result_address.is_some()) {
+        return Err(String::from("Only a round offer has a round number"));
+    }
+    if let Some(match_address) = &offer.match_address {
+        let match_: Match = handle_get_match(match_address.clone())?;
+        if offer_author_address == offer.challenger_id
+            || !match_.participants.contains(&offer_author_address)
+            || !match_.participants.contains(&offer.challenger_id) {
+            return Err(String::from("Round offer players are not the match participants."));
+        }
+        if offer.format_id != match_.format_id {
+            return Err(String::from("Round offer does not match the match format"));
+        }
+        // round 1 follows no result, every later round follows a result of the round before it in this match
+        let round: u64 = offer.round.unwrap_or(0);
+        let follows_previous_round: bool = match &offer.previous_result_address {
+            None => round == 1,
+            Some(result_address) => {
+                let previous_offer: Offer = handle_get_offer(get_outcome_offer_address(result_address)?)?;
+                round > 1 && previous_offer.match_address.as_ref() == Some(match_address) && previous_offer.round == Some(round - 1)
+            },
+        };
+        if !follows_previous_round {
+            return Err(format!("Round offer cannot be round {} of this match", round));
+        }
+        if match_.alternate_roles && offer_author_address != match_.participants[(round as usize - 1) % 2] {
+            return Err(format!("Round {} must be hosted by the other participant", round));
+        }
+    }
+    Ok(())
+}
+
+fn validate_match(match_: Match, match_author_address: Address) -> Result<(), String> {
+    if match_.participants.len() != 2 || match_.participants[0] == match_.participants[1] {
+        return Err(String::from("A match is between two distinct participants"));
+    }
+    if !match_.participants.contains(&match_author_address) {
+        return Err(String::from("Match author is not a participant."));
+    }
+    match match_.rule {
+        MatchRule::BestOf(0) | MatchRule::FirstTo(0) => return Err(String::from("A match needs at least one round")),
+        _ => (),
+    }
+    if match_.component_limit == Some(0) {
+        return Err(String::from("A deck match allows at least one use of each component"));
+    }
+    for constraint in &match_.constraints {
+        match constraint {
+            MoveConstraint::MaxConsecutive(0) => return Err(String::from("A component must be playable at least once in a row")),
+            MoveConstraint::PlayAllBeforeRepeat(names) => {
+                let mut distinct_names: Vec<String> = names.clone();
+                distinct_names.sort();
+                distinct_names.dedup();
+                if names.is_empty() || distinct_names.len() != names.len() {
+                    return Err(String::from("Play-all constraint needs distinct component names"));
+                }
+            },
+            _ => (),
+        }
+    }
+    Ok(())
+}
+
+fn validate_match_result(match_result: MatchResult, result_author_address: Address) -> Result<(), String> {
+    let match_: Match = handle_get_match(match_result.match_address.clone())?;
+    if !match_.participants.contains(&result_author_address) {
+        return Err(String::from("Match result author is not a participant."));
+    }
+    if match_result != tally_match(&match_result.match_address)? {
+        return Err(String::from("Match result does not match its rounds"));
+    }
+    Ok(())
+}
 
-    let commitment = Commitment {
-        hash: hashstring,
-        offer_address: offer_address_,
-        host_id: host_id_,
-        format_id: offer.format_id,
+// The offer of the game a game result or forfeit claim decided
+fn get_outcome_offer_address(result_address: &Address) -> ZomeApiResult<Address> {
+    Ok(get_game(get_indexed_outcome(result_address)?.move_address())?.offer_address)
+}
+
+// The decided result of the round's first move, which the next round follows
+fn get_round_result_address(offer_address: &Address) -> ZomeApiResult<Address> {
+    for address in hdk::get_links(offer_address, "move")?.addresses().iter() {
+        if get_game(address)?.offer_address == *offer_address {
+            return match get_decided_outcome(address)? {
+                Some((result_address, _)) => Ok(result_address),
+                None => Err(String::from("Previous round has no result yet").into()),
+            };
+        }
+    }
+    Err(String::from("Previous round has no move yet").into())
+}
+
+// The round number of a round offer, counted from 1
+fn get_match_round(match_address: &Address, offer_address: &Address) -> ZomeApiResult<u64> {
+    match handle_get_match_rounds(match_address.clone())?.iter().position(|address| address == offer_address) {
+        Some(index) => Ok(index as u64 + 1),
+        None => Err(String::from("Offer is not a round of this match").into()),
+    }
+}
+
+// The participant's match commitment, the first if there were several
+fn get_match_commitment_address(match_address: &Address, participant: &Address) -> ZomeApiResult<Address> {
+    for address in hdk::get_links(match_address, "match_commitment")?.addresses().iter() {
+        if get_author(address)? == *participant {
+            return Ok(address.clone());
+        }
+    }
+    Err(String::from("No match commitment found").into())
+}
+
+// The challenger's round reveal for this move, skipping any linked reveal that does not prove this round
+fn get_round_reveal(move_address: &Address, game: &Game) -> ZomeApiResult<RoundReveal> {
+    for address in hdk::get_links(move_address, "round_reveal")?.addresses().iter() {
+        let round_reveal: RoundReveal = handle_get_round_reveal(address.clone())?;
+        if get_author(address)? == game.challenger_id && verify_round_reveal(&round_reveal, move_address, game).is_ok() {
+            return Ok(round_reveal);
+        }
+    }
+    Err(String::from("Challenger has not revealed the round yet").into())
+}
+
+// (player, component) for each component played in the rounds of a match before this round's offer, in round order.
+// Components still hidden behind a commitment are not known yet and are left out.
+fn get_earlier_components(match_address: &Address, offer_address: &Address) -> ZomeApiResult<Vec<(Address, Component)>> {
+    let mut components: Vec<(Address, Component)> = Vec::new();
+    for round_offer_address in handle_get_match_rounds(match_address.clone())? {
+        if round_offer_address == *offer_address {
+            break;
+        }
+        let move_address: Address = match hdk::get_links(&round_offer_address, "move")?.addresses().iter().next() {
+            Some(move_address) => move_address.clone(),
+            None => continue,
+        };
+        let game: Game = get_game(&move_address)?;
+        match game.offer.protocol {
+            Protocol::Asymmetric => {
+                if let Some(host_component) = game.host_component {
+                    components.push((game.host_id.clone(), host_component));
+                }
+                if let Some(address) = hdk::get_links(&move_address, "game_result")?.addresses().iter().next() {
+                    let game_result: GameResult = handle_get_game_result(address.clone())?;
+                    components.push((game.challenger_id.clone(), game_result.reveal().component.clone()));
+                }
+            },
+            Protocol::Symmetric => for (author, move_reveal) in get_move_reveals(&move_address)? {
+                components.push((author, move_reveal.reveal.component));
+            },
+        }
+    }
+    Ok(components)
+}
+
+// Checks a round's component against the player's earlier rounds: the deck limit and the match's move constraints
+fn validate_component_use(offer: &Offer, offer_address: &Address, player: &Address, component: &Component) -> Result<(), String> {
+    let match_address: &Address = match &offer.match_address {
+        Some(match_address) => match_address,
+        None => return Ok(()),
     };
+    let match_: Match = handle_get_match(match_address.clone())?;
+    if match_.component_limit.is_none() && match_.constraints.is_empty() {
+        return Ok(());
+    }
+    let history: Vec<Component> = get_earlier_components(match_address, offer_address)?
+        .into_iter()
+        .filter(|(author, _)| author == player)
+        .map(|(_, earlier)| earlier)
+        .collect();
+    if let Some(component_limit) = match_.component_limit {
+        let uses: u64 = history.iter().filter(|earlier| earlier.name == component.name).count() as u64;
+        if uses >= component_limit {
+            return Err(format!("{} has already been played {} times in this match", component.name, uses));
+        }
+    }
+    for constraint in &match_.constraints {
+        constraint.check(&history, component)?;
+    }
+    Ok(())
+}
 
-    let entry = Entry::App("commitment".into(), commitment.into());
-    let address = hdk::commit_entry(&entry)?;
-    Ok(address)
+// Whether a match with these wins after this many counted rounds is over
+fn is_match_decided(rule: &MatchRule, wins: &[u64], rounds: u64) -> bool {
+    let most_wins: u64 = wins.iter().cloned().max().unwrap_or(0);
+    match rule {
+        MatchRule::BestOf(n) => most_wins > n / 2 || rounds >= *n,
+        MatchRule::FirstTo(k) => most_wins >= *k,
+    }
+}
+
+// The outcome of a round's game, counting only results about this move of this match's round.
+// A game result only counts if it is the result the move and its reveals actually give.
+fn get_round_outcome(match_address: &Address, offer_address: &Address, move_address: &Address) -> ZomeApiResult<Option<Outcome>> {
+    let game: Game = get_game(move_address)?;
+    if game.offer_address != *offer_address || game.offer.match_address.as_ref() != Some(match_address) {
+        return Err(String::from("Move is not a round of this match").into());
+    }
+    for (result_address, game_result) in get_game_results(move_address)? {
+        if is_overturned(&result_address)? {
+            continue;
+        }
+        let (host_reveal, puzzle_solution) = match &game_result {
+            GameResult::Win { host_reveal, puzzle_solution, .. } => (host_reveal.clone(), puzzle_solution.clone()),
+            GameResult::Draw { host_reveal, puzzle_solution, .. } => (host_reveal.clone(), puzzle_solution.clone()),
+        };
+        let derived: GameResult = create_game_result(game_result.reveal().clone(), move_address.clone(), game.host_id.clone(), host_reveal, puzzle_solution)?;
+        if derived == game_result {
+            return Ok(Some(derived.into()));
+        }
+    }
+    for (claim_address, claim) in get_forfeit_claims(move_address)? {
+        let players: Vec<&Address> = vec![&game.host_id, &game.challenger_id];
+        if !is_overturned(&claim_address)? && players.contains(&&claim.winner_id) && players.contains(&&claim.loser_id) {
+            return Ok(Some(claim.into()));
+        }
+    }
+    Ok(None)
+}
+
+// Counts round outcomes in order until the match is decided, failing while rounds are missing
+fn tally_match(match_address: &Address) -> ZomeApiResult<MatchResult> {
+    let match_: Match = handle_get_match(match_address.clone())?;
+    let mut wins: Vec<u64> = vec![0; match_.participants.len()];
+    let mut counted_rounds: u64 = 0;
+    let mut round_move_addresses: Vec<Address> = Vec::new();
+    for (round, offer_address) in handle_get_match_rounds(match_address.clone())?.iter().enumerate() {
+        if is_match_decided(&match_.rule, &wins, counted_rounds) {
+            break;
+        }
+        let mut round_moves: Vec<Address> = Vec::new();
+        for address in hdk::get_links(offer_address, "move")?.addresses().iter() {
+            if get_game(address)?.offer_address == *offer_address {
+                round_moves.push(address.clone());
+            }
+        }
+        let move_address: Address = match round_moves.into_iter().next() {
+            Some(address) => address,
+            None => return Err(format!("Round {} has no move yet", round + 1).into()),
+        };
+        match get_round_outcome(match_address, offer_address, &move_address)? {
+            Some(Outcome::Win { winner_id, .. }) => {
+                if let Some(index) = match_.participants.iter().position(|participant| *participant == winner_id) {
+                    wins[index] += 1;
+                }
+                counted_rounds += 1;
+            },
+            Some(Outcome::Draw { .. }) => if !match_.replay_draws {
+                counted_rounds += 1;
+            },
+            None => return Err(format!("Round {} has no result yet", round + 1).into()),
+        }
+        round_move_addresses.push(move_address);
+    }
+    if !is_match_decided(&match_.rule, &wins, counted_rounds) {
+        return Err(String::from("Match threshold has not been reached").into());
+    }
+
+    let most_wins: u64 = wins.iter().cloned().max().unwrap_or(0);
+    let leaders: Vec<&Address> = match_.participants
+        .iter()
+        .zip(wins.iter())
+        .filter(|(_, participant_wins)| **participant_wins == most_wins)
+        .map(|(participant, _)| participant)
+        .collect();
+    Ok(MatchResult {
+        match_address: match_address.clone(),
+        round_move_addresses: round_move_addresses,
+        winner_id: if leaders.len() == 1 { Some(leaders[0].clone()) } else { None },
+        wins: wins,
+    })
+}
+
+fn validate_commitment(commitment: &Commitment, commitment_author_address: &Address) -> Result<(), String> {
+    let offer_author_address: Address = get_author(&commitment.offer_address)?;
+    let offer: Offer = handle_get_offer(commitment.offer_address.clone())?;
+
+    if offer_author_address != commitment.host_id {
+        return Err(String::from("Commitment host id does not match offer author."));
+    }
+    if *commitment_author_address != offer.challenger_id {
+        return Err(String::from("Commitment author does not match challenger id."));
+    }
+    if offer.games.is_some() {
+        return Err(String::from("Offer is for a game set"));
+    }
+    verify_content(commitment_author_address, &commitment.signature, &commitment_content(commitment))?;
+    if !allowed_hash_algorithms(&commitment.format_id).contains(&commitment.hash_algorithm) {
+        return Err(String::from("Hash algorithm is not allowed"));
+    }
+    if let Some(puzzle) = &commitment.timelock {
+        // the sealed reveal itself can only be checked once someone solves the puzzle
+        let policy: TimelockPolicy = timelock_policy();
+        if (timelock::parse_number(&puzzle.modulus)?.bits() as u64) < policy.min_modulus_bits.unwrap_or(2048) {
+            return Err(String::from("Time-lock modulus is too small"));
+        }
+        if puzzle.squarings > offer.reveal_deadline.saturating_mul(policy.squarings_per_second.unwrap_or(100_000)) {
+            return Err(String::from("Time-lock puzzle takes longer to solve than the reveal deadline"));
+        }
+        timelock::parse_number(&puzzle.base)?;
+        canonical::from_hex(&puzzle.sealed_reveal)?;
+    }
+    Ok(())
+}
+
+fn validate_move(move_: &Move, move_author_address: &Address) -> Result<(), String> {
+    if let Some(offer_address) = &move_.offer_address {
+        return validate_round_move(move_, offer_address, move_author_address);
+    }
+    let commitment_author_address: Address = get_author(&move_.commitment_address)?;
+    let commitment: Commitment = handle_get_commitment(move_.commitment_address.clone())?;
+    let offer: Offer = handle_get_offer(commitment.offer_address.clone())?;
+
+    if *move_author_address != commitment.host_id {
+        return Err(String::from("Move author does not match host id."));
+    }
+    if commitment_author_address != move_.challenger_id {
+        return Err(String::from("Move challenger id does not match commitment author."));
+    }
+    verify_content(move_author_address, &move_.signature, &move_content(move_))?;
+    if offer.protocol != Protocol::Asymmetric {
+        return Err(String::from("Offer does not use the asymmetric protocol"));
+    }
+    // TODO assert(_move.component is not in format);
+    validate_component_use(&offer, &commitment.offer_address, move_author_address, &move_.component)
+}
+
+// A move on a round offer, answering the challenger's match commitment
+fn validate_round_move(move_: &Move, offer_address: &Address, move_author_address: &Address) -> Result<(), String> {
+    let offer_author_address: Address = get_author(offer_address)?;
+    let offer: Offer = handle_get_offer(offer_address.clone())?;
+    let match_commitment: MatchCommitment = handle_get_match_commitment(move_.commitment_address.clone())?;
+
+    if *move_author_address != offer_author_address {
+        return Err(String::from("Move author does not match offer author."));
+    }
+    if get_author(&move_.commitment_address)? != offer.challenger_id || move_.challenger_id != offer.challenger_id {
+        return Err(String::from("Match commitment author does not match challenger id."));
+    }
+    if offer.match_address.as_ref() != Some(&match_commitment.match_address) {
+        return Err(String::from("Offer is not a round of the committed match"));
+    }
+    if move_.hash != match_commitment.root || move_.format_id != offer.format_id {
+        return Err(String::from("Move does not match match commitment"));
+    }
+    if offer.protocol != Protocol::Asymmetric {
+        return Err(String::from("Offer does not use the asymmetric protocol"));
+    }
+    let round: u64 = get_match_round(&match_commitment.match_address, offer_address)?;
+    if round > match_commitment.rounds {
+        return Err(format!("Match commitment does not cover round {}", round));
+    }
+    verify_content(move_author_address, &move_.signature, &move_content(move_))?;
+    validate_component_use(&offer, offer_address, move_author_address, &move_.component)
+}
+
+fn validate_match_commitment(match_commitment: &MatchCommitment, commitment_author_address: &Address) -> Result<(), String> {
+    let match_: Match = handle_get_match(match_commitment.match_address.clone())?;
+
+    if !match_.participants.contains(commitment_author_address) {
+        return Err(String::from("Match commitment author is not a participant."));
+    }
+    if match_commitment.rounds == 0 {
+        return Err(String::from("A match commitment needs at least one round"));
+    }
+    verify_content(commitment_author_address, &match_commitment.signature, &match_commitment_content(match_commitment))?;
+    if !allowed_hash_algorithms(&match_.format_id).contains(&match_commitment.hash_algorithm) {
+        return Err(String::from("Hash algorithm is not allowed"));
+    }
+    // a second root would let the challenger pick, round by round, which moves to reveal
+    let address: Address = hdk::entry_address(&Entry::App("match_commitment".into(), match_commitment.clone().into()))?;
+    for other_address in hdk::get_links(&match_commitment.match_address, "match_commitment")?.addresses().iter() {
+        if *other_address != address && get_author(other_address)? == *commitment_author_address {
+            return Err(String::from("Participant has already committed to this match"));
+        }
+    }
+    Ok(())
+}
+
+fn validate_round_reveal(round_reveal: &RoundReveal, reveal_author_address: &Address) -> Result<(), String> {
+    let game: Game = get_game(&round_reveal.move_address)?;
+    if *reveal_author_address != game.challenger_id {
+        return Err(String::from("Round reveal author does not match challenger id."));
+    }
+    verify_round_reveal(round_reveal, &round_reveal.move_address, &game)?;
+    validate_component_use(&game.offer, &game.offer_address, reveal_author_address, &round_reveal.reveal.component)
+}
+
+// Whether the round reveal proves the challenger's component for this move's round of the match commitment
+fn verify_round_reveal(round_reveal: &RoundReveal, move_address: &Address, game: &Game) -> Result<(), String> {
+    if round_reveal.move_address != *move_address {
+        return Err(String::from("Round reveal is for another move"));
+    }
+    if !game.match_committed {
+        return Err(String::from("Move does not answer a match commitment"));
+    }
+    let match_commitment: MatchCommitment = handle_get_match_commitment(game.commitment_address.clone())?;
+    if round_reveal.round != get_match_round(&match_commitment.match_address, &game.offer_address)? {
+        return Err(String::from("Round reveal is for another round"));
+    }
+    validate_nonce(&round_reveal.reveal.nonce)?;
+    let leaf: HashString = calculate_hash(&round_leaf_preimage(&round_reveal.reveal, &match_commitment.match_address, round_reveal.round, &game.challenger_id), &game.hash_algorithm)?;
+    let index: usize = round_reveal.round as usize - 1;
+    if !merkle::verify(&leaf, index, match_commitment.rounds as usize, &round_reveal.proof, &match_commitment.root, &|left: &HashString, right: &HashString| merkle_node(left, right, &game.hash_algorithm))? {
+        return Err(String::from("Proof does not lead from the reveal to the match commitment root"));
+    }
+    Ok(())
+}
+
+fn validate_set_commitment(set_commitment: &SetCommitment, commitment_author_address: &Address) -> Result<(), String> {
+    let offer_author_address: Address = get_author(&set_commitment.offer_address)?;
+    let offer: Offer = handle_get_offer(set_commitment.offer_address.clone())?;
+
+    if offer_author_address != set_commitment.host_id {
+        return Err(String::from("Set commitment host id does not match offer author."));
+    }
+    if *commitment_author_address != offer.challenger_id {
+        return Err(String::from("Set commitment author does not match challenger id."));
+    }
+    if offer.games != Some(set_commitment.hashes.len() as u64) {
+        return Err(String::from("Set commitment does not hold one hash per game of the offer"));
+    }
+    if set_commitment.format_id != offer.format_id {
+        return Err(String::from("Set commitment does not match the offer format"));
+    }
+    verify_content(commitment_author_address, &set_commitment.signature, &set_commitment_content(set_commitment))?;
+    if !allowed_hash_algorithms(&set_commitment.format_id).contains(&set_commitment.hash_algorithm) {
+        return Err(String::from("Hash algorithm is not allowed"));
+    }
+    Ok(())
+}
+
+fn validate_set_move(set_move: &SetMove, move_author_address: &Address) -> Result<(), String> {
+    let commitment_author_address: Address = get_author(&set_move.commitment_address)?;
+    let set_commitment: SetCommitment = handle_get_set_commitment(set_move.commitment_address.clone())?;
+
+    if *move_author_address != set_commitment.host_id {
+        return Err(String::from("Set move author does not match host id."));
+    }
+    if commitment_author_address != set_move.challenger_id {
+        return Err(String::from("Set move challenger id does not match set commitment author."));
+    }
+    if set_move.components.len() != set_commitment.hashes.len() || set_move.format_id != set_commitment.format_id {
+        return Err(String::from("Set move does not match set commitment"));
+    }
+    verify_content(move_author_address, &set_move.signature, &set_move_content(set_move))
+}
+
+fn validate_set_result(set_result: SetResult, result_author_address: &Address) -> Result<(), String> {
+    let set_move: SetMove = handle_get_set_move(set_result.move_address.clone())?;
+    let set_commitment: SetCommitment = handle_get_set_commitment(set_move.commitment_address.clone())?;
+
+    if *result_author_address != set_move.challenger_id {
+        return Err(String::from("Set result author does not match challenger id."));
+    }
+    if set_result.reveals.len() != set_commitment.hashes.len() {
+        return Err(String::from("Set result needs one reveal per game"));
+    }
+    for (game, (reveal, hash)) in set_result.reveals.iter().zip(set_commitment.hashes.iter()).enumerate() {
+        validate_nonce(&reveal.nonce)?;
+        if *hash != calculate_hash(&set_preimage(reveal, &set_commitment.offer_address, &set_move.challenger_id, game as u64), &set_commitment.hash_algorithm)? {
+            return Err(format!("Hash of game {} does not match its reveal", game + 1));
+        }
+    }
+    if set_result != create_set_result(&set_move, &set_result.move_address, set_result.reveals.clone())? {
+        return Err(String::from("Set results do not match"));
+    }
+    Ok(())
+}
+
+fn validate_free_for_all(free_for_all: FreeForAll, game_author_address: Address) -> Result<(), String> {
+    let mut distinct_players: Vec<Address> = free_for_all.players.clone();
+    distinct_players.sort();
+    distinct_players.dedup();
+    if free_for_all.players.len() < 2 || distinct_players.len() != free_for_all.players.len() {
+        return Err(String::from("A free-for-all is between at least two distinct players"));
+    }
+    if !free_for_all.players.contains(&game_author_address) {
+        return Err(String::from("Free-for-all author is not a player."));
+    }
+    Ok(())
+}
+
+// (author, address) of each commitment to a free-for-all
+fn get_free_for_all_commitments(game_address: &Address) -> ZomeApiResult<Vec<(Address, Address)>> {
+    let mut commitments: Vec<(Address, Address)> = Vec::new();
+    for address in hdk::get_links(game_address, "commitment")?.addresses().iter() {
+        if handle_get_free_for_all_commitment(address.clone())?.game_address == *game_address {
+            commitments.push((get_author(address)?, address.clone()));
+        }
+    }
+    Ok(commitments)
+}
+
+// The player's one commitment to the game. A player who committed more than once can't be ranked,
+// since they could reveal whichever commitment suits them.
+fn get_player_commitment(game_address: &Address, player: &Address) -> ZomeApiResult<Address> {
+    let commitments: Vec<Address> = get_free_for_all_commitments(game_address)?
+        .into_iter()
+        .filter(|(author, _)| author == player)
+        .map(|(_, address)| address)
+        .collect();
+    match commitments.len() {
+        0 => Err(format!("{} has not committed yet", player).into()),
+        1 => Ok(commitments[0].clone()),
+        _ => Err(format!("{} committed more than once", player).into()),
+    }
+}
+
+fn validate_free_for_all_commitment(commitment: &FreeForAllCommitment, commitment_author_address: &Address) -> Result<(), String> {
+    let free_for_all: FreeForAll = handle_get_free_for_all(commitment.game_address.clone())?;
+
+    if !free_for_all.players.contains(commitment_author_address) {
+        return Err(String::from("Commitment author is not a player."));
+    }
+    verify_content(commitment_author_address, &commitment.signature, &free_for_all_commitment_content(commitment))?;
+    if !allowed_hash_algorithms(&free_for_all.format_id).contains(&commitment.hash_algorithm) {
+        return Err(String::from("Hash algorithm is not allowed"));
+    }
+    let address: Address = hdk::entry_address(&Entry::App("free_for_all_commitment".into(), commitment.clone().into()))?;
+    for (author, other_address) in get_free_for_all_commitments(&commitment.game_address)? {
+        if other_address != address && author == *commitment_author_address {
+            return Err(String::from("Player has already committed to this game"));
+        }
+    }
+    Ok(())
+}
+
+fn validate_free_for_all_reveal(free_for_all_reveal: &FreeForAllReveal, reveal_author_address: &Address) -> Result<(), String> {
+    let commitment_author_address: Address = get_author(&free_for_all_reveal.commitment_address)?;
+    let commitment: FreeForAllCommitment = handle_get_free_for_all_commitment(free_for_all_reveal.commitment_address.clone())?;
+    let free_for_all: FreeForAll = handle_get_free_for_all(commitment.game_address.clone())?;
+
+    if *reveal_author_address != commitment_author_address {
+        return Err(String::from("Reveal author does not match commitment author."));
+    }
+    // revealing early would let players who have not committed yet answer the reveal
+    let committed: Vec<Address> = get_free_for_all_commitments(&commitment.game_address)?
+        .into_iter()
+        .map(|(author, _)| author)
+        .collect();
+    if !free_for_all.players.iter().all(|player| committed.contains(player)) {
+        return Err(String::from("Every player has not committed yet"));
+    }
+    validate_nonce(&free_for_all_reveal.reveal.nonce)?;
+    if commitment.hash != calculate_hash(&free_for_all_preimage(&free_for_all_reveal.reveal, &commitment.game_address, reveal_author_address), &commitment.hash_algorithm)? {
+        return Err(String::from("Hash does not match hash of reveal"));
+    }
+    Ok(())
+}
+
+fn validate_free_for_all_ranking(ranking: FreeForAllRanking, ranking_author_address: &Address) -> Result<(), String> {
+    let free_for_all: FreeForAll = handle_get_free_for_all(ranking.game_address.clone())?;
+
+    if !free_for_all.players.contains(ranking_author_address) {
+        return Err(String::from("Ranking author is not a player."));
+    }
+    if ranking.reveal_addresses.len() != free_for_all.players.len() {
+        return Err(String::from("Ranking needs one reveal per player"));
+    }
+    // each reveal opens the player's one commitment to this game
+    for (player, reveal_address) in free_for_all.players.iter().zip(ranking.reveal_addresses.iter()) {
+        let free_for_all_reveal: FreeForAllReveal = handle_get_free_for_all_reveal(reveal_address.clone())?;
+        if free_for_all_reveal.commitment_address != get_player_commitment(&ranking.game_address, player)? {
+            return Err(String::from("Ranking reveals are not the players' reveals for this game"));
+        }
+    }
+    if ranking != create_free_for_all_ranking(&ranking.game_address, ranking.reveal_addresses.clone())? {
+        return Err(String::from("Rankings do not match"));
+    }
+    Ok(())
+}
+
+fn validate_sealed_move(sealed_move: &SealedMove, sealed_move_author_address: &Address) -> Result<(), String> {
+    let commitment_author_address: Address = get_author(&sealed_move.commitment_address)?;
+    let commitment: Commitment = handle_get_commitment(sealed_move.commitment_address.clone())?;
+    let offer: Offer = handle_get_offer(commitment.offer_address.clone())?;
+
+    if *sealed_move_author_address != commitment.host_id {
+        return Err(String::from("Sealed move author does not match host id."));
+    }
+    if commitment_author_address != sealed_move.challenger_id {
+        return Err(String::from("Sealed move challenger id does not match commitment author."));
+    }
+    if sealed_move.hash != commitment.hash || sealed_move.format_id != commitment.format_id {
+        return Err(String::from("Sealed move does not match commitment"));
+    }
+    if sealed_move.host_hash == commitment.hash {
+        return Err(String::from("Sealed move host hash copies the challenger's hash"));
+    }
+    if offer.protocol != Protocol::Symmetric {
+        return Err(String::from("Offer does not use the symmetric protocol"));
+    }
+    verify_content(sealed_move_author_address, &sealed_move.signature, &sealed_move_content(sealed_move))
+}
+
+fn validate_game_result_entry(game_result: GameResult, result_header: &ChainHeader) -> Result<(), String> {
+    match game_result.clone() {
+        GameResult::Win {
+            reveal,
+            move_address,
+            winner_id: _, // validated by checking game result
+            loser_id: _,  // validated by checking game result
+            format_id: _,
+            host_reveal,
+            puzzle_solution,
+        } => validate_game_result(game_result, reveal, host_reveal, puzzle_solution, move_address, result_header),
+        GameResult::Draw {
+            reveal,
+            move_address,
+            players: _, // validated by checking game result
+            format_id: _,
+            host_reveal,
+            puzzle_solution,
+        } => validate_game_result(game_result, reveal, host_reveal, puzzle_solution, move_address, result_header),
+    }
+}
+
+fn validate_game_result(game_result: GameResult, reveal: Reveal, host_reveal: Option<Reveal>, puzzle_solution: Option<String>, move_address: Address, result_header: &ChainHeader) -> Result<(), String> {
+    let move_author: Address = get_author(&move_address)?;
+    let game: Game = get_game(&move_address)?;
+
+    validate_game_result_author(&game, &move_address, result_header, &puzzle_solution)?;
+    validate_nonce(&reveal.nonce)?;
+    if let Some(host_reveal) = &host_reveal {
+        validate_nonce(&host_reveal.nonce)?;
+    }
+    if let Some(puzzle_solution) = puzzle_solution.clone() {
+        let puzzle: TimelockPuzzle = match game.timelock.clone() {
+            Some(puzzle) => puzzle,
+            None => return Err(String::from("Commitment is not time-locked")),
+        };
+        if unseal_reveal(&puzzle, &puzzle_solution)? != reveal {
+            return Err(String::from("Puzzle solution does not unseal the reveal"));
+        }
+    }
+    // the reveal only matches if it is bound to this game's offer and challenger,
+    // or for a match commitment, if the challenger proved it for this round
+    if game.match_committed {
+        let round_reveal: RoundReveal = get_round_reveal(&move_address, &game)?;
+        verify_round_reveal(&round_reveal, &move_address, &game)?;
+        if round_reveal.reveal != reveal {
+            return Err(String::from("Reveal does not match the round reveal"));
+        }
+    } else if game.challenger_hash != calculate_hash(&commitment_preimage(&reveal, &game.offer_address, &game.challenger_id), &game.hash_algorithm)? {
+        return Err(String::from("Move hash does not match hash of reveal for this game"));
+    }
+    match (&game.host_hash, &host_reveal) {
+        (Some(host_hash), Some(host_reveal)) => if *host_hash != calculate_hash(&host_preimage(host_reveal, &game.offer_address, &game.challenger_id, &game.host_id), &game.hash_algorithm)? {
+            return Err(String::from("Sealed move hash does not match hash of host reveal"));
+        },
+        (None, None) => (),
+        _ => return Err(String::from("Host reveal does not match protocol")),
+    }
+    validate_component_use(&game.offer, &game.offer_address, &game.challenger_id, &reveal.component)?;
+    if let Some(host_reveal) = &host_reveal {
+        validate_component_use(&game.offer, &game.offer_address, &game.host_id, &host_reveal.component)?;
+    }
+    if game_result != create_game_result(reveal, move_address.clone(), move_author, host_reveal, puzzle_solution)? {
+        return Err(String::from("Game results do not match"));
+    }
+    // TODO Assert!(reveal component is in the format);
+    Ok(())
+}
+
+fn validate_game_result_author(game: &Game, move_address: &Address, result_header: &ChainHeader, puzzle_solution: &Option<String>) -> Result<(), String> {
+    let result_author_address: Address = author_from_header(result_header)?;
+
+    // the arbiter, or anyone who solved a time-lock puzzle, only steps in once the challenger has stalled past the deadline
+    if game.offer.arbiter_id.as_ref() == Some(&result_author_address) || puzzle_solution.is_some() {
+        let deadline: u64 = timestamp_seconds(&get_header(move_address)?)? + game.offer.reveal_deadline;
+        if timestamp_seconds(result_header)? < deadline {
+            return Err(String::from("Reveal deadline has not passed"));
+        }
+        return Ok(());
+    }
+    match game.offer.protocol {
+        Protocol::Asymmetric => if result_author_address != game.challenger_id {
+            return Err(String::from("Game result author does not match challenger id."));
+        },
+        Protocol::Symmetric => if result_author_address != game.challenger_id && result_author_address != game.host_id {
+            return Err(String::from("Game result author is not a player."));
+        },
+    }
+    Ok(())
+}
+
+fn validate_move_reveal(move_reveal: MoveReveal, reveal_author_address: Address) -> Result<(), String> {
+    let sealed_move_author: Address = get_author(&move_reveal.move_address)?;
+    let sealed_move: SealedMove = handle_get_sealed_move(move_reveal.move_address.clone())?;
+    let commitment: Commitment = handle_get_commitment(sealed_move.commitment_address.clone())?;
+    let offer: Offer = handle_get_offer(commitment.offer_address.clone())?;
+
+    let (hash, preimage): (HashString, Vec<u8>) = if reveal_author_address == sealed_move_author {
+        (sealed_move.host_hash, host_preimage(&move_reveal.reveal, &commitment.offer_address, &sealed_move.challenger_id, &sealed_move_author))
+    } else if reveal_author_address == sealed_move.challenger_id {
+        (sealed_move.hash, commitment_preimage(&move_reveal.reveal, &commitment.offer_address, &sealed_move.challenger_id))
+    } else {
+        return Err(String::from("Move reveal author is not a player."));
+    };
+    validate_nonce(&move_reveal.reveal.nonce)?;
+    if hash != calculate_hash(&preimage, &commitment.hash_algorithm)? {
+        return Err(String::from("Hash does not match hash of reveal"));
+    }
+    validate_component_use(&offer, &commitment.offer_address, &reveal_author_address, &move_reveal.reveal.component)
+}
+
+// The player who confirms a game result: the host, unless the host reported the result themselves
+fn get_confirmer(game_result_address: &Address) -> ZomeApiResult<Address> {
+    let result_author: Address = get_author(game_result_address)?;
+    let game_result: GameResult = handle_get_game_result(game_result_address.clone())?;
+    let game: Game = get_game(game_result.move_address())?;
+    Ok(if result_author == game.host_id { game.challenger_id } else { game.host_id })
+}
+
+fn validate_result_confirmation_link(validation_data: hdk::LinkValidationData) -> Result<(), String> {
+    if let hdk::LinkValidationData::LinkAdd{link, validation_data: validation_} = validation_data {
+        let link_author_address: Address = author_from_header(&validation_.package.chain_header)?;
+        let confirmation: ResultConfirmation = handle_get_result_confirmation(link.link().target().clone())?;
+        if confirmation.game_result_address != *link.link().base() {
+            return Err(String::from("Confirmation is of another game result"));
+        }
+        if get_author(link.link().target())? != link_author_address || link_author_address != get_confirmer(link.link().base())? {
+            return Err(String::from("Link author is not the other player."));
+        }
+    }
+    Ok(())
+}
+
+fn validate_result_confirmation(confirmation: ResultConfirmation, confirmation_author_address: Address) -> Result<(), String> {
+    if confirmation_author_address != get_confirmer(&confirmation.game_result_address)? {
+        return Err(String::from("Result confirmation author is not the other player."));
+    }
+    verify_content(&confirmation_author_address, &confirmation.signature, &confirmation_content(&confirmation.game_result_address))
+}
+
+fn validate_nonce(nonce: &str) -> Result<(), String> {
+    if nonce.len() < MIN_NONCE_LENGTH {
+        return Err(format!("Nonce must be at least {} characters", MIN_NONCE_LENGTH));
+    }
+    if !nonce.chars().all(|character| character.is_ascii_alphanumeric()) {
+        return Err(String::from("Nonce must be alphanumeric"));
+    }
+    Ok(())
+}
+
+fn validate_forfeit_claim(claim: ForfeitClaim, claim_header: &ChainHeader) -> Result<(), String> {
+    let claim_author: Address = author_from_header(claim_header)?;
+    let game: Game = get_game(&claim.move_address)?;
+
+    if claim.winner_id != claim_author {
+        return Err(String::from("Forfeit claim author does not match winner id."));
+    }
+    if game.offer.arbiter_id.is_some() {
+        return Err(String::from("Arbitrated games cannot be forfeited"));
+    }
+    match game.offer.protocol {
+        // only the host can be waiting on a reveal
+        Protocol::Asymmetric => if claim_author != game.host_id || claim.loser_id != game.challenger_id {
+            return Err(String::from("Forfeit claim author does not match host."));
+        },
+        // either player can be waiting, as long as they have revealed themselves
+        Protocol::Symmetric => {
+            let is_host_claim = claim_author == game.host_id && claim.loser_id == game.challenger_id;
+            let is_challenger_claim = claim_author == game.challenger_id && claim.loser_id == game.host_id;
+            if !is_host_claim && !is_challenger_claim {
+                return Err(String::from("Forfeit claim players do not match game."));
+            }
+            let revealed: Vec<Address> = get_move_reveals(&claim.move_address)?
+                .into_iter()
+                .map(|(author, _)| author)
+                .collect();
+            if !revealed.contains(&claim_author) {
+                return Err(String::from("Forfeit claim author has not revealed"));
+            }
+            if revealed.contains(&claim.loser_id) {
+                return Err(String::from("Opponent has already revealed"));
+            }
+        },
+    }
+    if claim.format_id != game.format_id {
+        return Err(String::from("Forfeit claim does not match move"));
+    }
+    let deadline: u64 = timestamp_seconds(&get_header(&claim.move_address)?)? + game.offer.reveal_deadline;
+    if timestamp_seconds(claim_header)? < deadline {
+        return Err(String::from("Reveal deadline has not passed"));
+    }
+    Ok(())
+}
+
+// The entries a dispute can be filed against
+enum DisputeSubject {
+    Commitment(Commitment),
+    Move(Move),
+    SealedMove(SealedMove),
+    GameResult(GameResult),
+    ForfeitClaim(ForfeitClaim),
+}
+
+fn get_dispute_subject(subject_address: &Address) -> ZomeApiResult<DisputeSubject> {
+    match hdk::get_entry(subject_address) {
+        Ok(Some(Entry::App(entry_type, api_result))) => match String::from(entry_type).as_ref() {
+            "commitment" => Ok(DisputeSubject::Commitment(api_result.try_into()?)),
+            "move" => Ok(DisputeSubject::Move(api_result.try_into()?)),
+            "sealed_move" => Ok(DisputeSubject::SealedMove(api_result.try_into()?)),
+            "game_result" => Ok(DisputeSubject::GameResult(api_result.try_into()?)),
+            "forfeit_claim" => Ok(DisputeSubject::ForfeitClaim(api_result.try_into()?)),
+            _ => Err(String::from("Only commitments, moves, game results and forfeit claims can be disputed").into()),
+        },
+        _ => Err(String::from("No dispute subject found").into()),
+    }
+}
+
+// Re-runs the validation of the subject entry as it stands now
+fn evaluate_dispute(subject_address: &Address, reason: &DisputeReason) -> ZomeApiResult<Verdict> {
+    let subject: DisputeSubject = get_dispute_subject(subject_address)?;
+    if *reason == DisputeReason::Timeout {
+        // deadlines are checked against header timestamps, which cannot tell a stalling player from a wrong clock
+        return Ok(Verdict::Inconclusive);
+    }
+    let subject_header: ChainHeader = get_header(subject_address)?;
+    let subject_author: Address = author_from_header(&subject_header)?;
+    let check: Result<(), String> = match subject {
+        DisputeSubject::Commitment(commitment) => validate_commitment(&commitment, &subject_author),
+        DisputeSubject::Move(move_) => validate_move(&move_, &subject_author),
+        DisputeSubject::SealedMove(sealed_move) => validate_sealed_move(&sealed_move, &subject_author),
+        DisputeSubject::GameResult(game_result) => validate_game_result_entry(game_result, &subject_header),
+        DisputeSubject::ForfeitClaim(claim) => validate_forfeit_claim(claim, &subject_header),
+    };
+    match check {
+        Ok(()) => Ok(Verdict::Rejected),
+        Err(finding) => Ok(Verdict::Upheld { finding }),
+    }
+}
+
+// (host, challenger) of the subject's game, taken from the offer since the subject itself may name the wrong agents
+fn get_dispute_players(subject_address: &Address) -> ZomeApiResult<(Address, Address)> {
+    let offer_address: Address = match get_dispute_subject(subject_address)? {
+        DisputeSubject::Commitment(commitment) => commitment.offer_address,
+        DisputeSubject::Move(move_) => handle_get_commitment(move_.commitment_address)?.offer_address,
+        DisputeSubject::SealedMove(sealed_move) => handle_get_commitment(sealed_move.commitment_address)?.offer_address,
+        DisputeSubject::GameResult(game_result) => get_game(game_result.move_address())?.offer_address,
+        DisputeSubject::ForfeitClaim(claim) => get_game(&claim.move_address)?.offer_address,
+    };
+    let offer: Offer = handle_get_offer(offer_address.clone())?;
+    Ok((get_author(&offer_address)?, offer.challenger_id))
+}
+
+// Whether any dispute against the entry has been upheld, by the checks or by a jury
+fn is_overturned(subject_address: &Address) -> ZomeApiResult<bool> {
+    for dispute_address in handle_get_disputes(subject_address.clone())? {
+        if let Verdict::Upheld { .. } = handle_get_dispute_verdict(dispute_address)? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
 }
 
-pub fn handle_new_move(component_: Component, commitment_address_: Address, challenger_id_: Address) -> ZomeApiResult<Address> {
-    let commitment: Commitment = handle_get_commitment(commitment_address_.clone())?;
-
-    let move_ = Move {
-        component: component_,
-        commitment_address: commitment_address_,
-        challenger_id: challenger_id_,
-        hash: commitment.hash.clone(),
-        format_id: commitment.format_id,
-    };
+fn validate_dispute(dispute: Dispute, dispute_header: &ChainHeader) -> Result<(), String> {
+    let dispute_author: Address = author_from_header(dispute_header)?;
+    let (host_id, challenger_id) = get_dispute_players(&dispute.subject_address)?;
+    if dispute_author != host_id && dispute_author != challenger_id {
+        return Err(String::from("Dispute author is not a player."));
+    }
+    for address in dispute.referenced_addresses.iter() {
+        if hdk::get_entry(address)?.is_none() {
+            return Err(format!("Referenced entry not found: {}", address));
+        }
+    }
+    let mut juror_pool: Vec<Address> = dispute.juror_pool.clone();
+    juror_pool.sort();
+    juror_pool.dedup();
+    if juror_pool != dispute.juror_pool || juror_pool.contains(&host_id) || juror_pool.contains(&challenger_id) {
+        return Err(String::from("Juror pool must be sorted, distinct agents other than the players"));
+    }
+    if dispute.verdict != evaluate_dispute(&dispute.subject_address, &dispute.reason)? {
+        return Err(String::from("Dispute verdict does not match the checks"));
+    }
+    Ok(())
+}
 
-    let entry = Entry::App("move".into(), move_.into());
-    let address = hdk::commit_entry(&entry)?;
-    Ok(address)
+// Every game result and forfeit claim of a format is linked from the anchor of the day it was committed,
+// and each day's anchor from this one, so ratings can replay them without reading days a snapshot covers
+fn outcome_index(format_id: &str) -> Entry {
+    let anchor = Anchor { name: format!("outcomes {}", format_id) };
+    Entry::App("anchor".into(), anchor.into())
 }
 
-pub fn handle_new_game_result(reveal: Reveal, move_address: Address, host_id: Address) -> ZomeApiResult<Address> {
-    let game_result: GameResult = create_game_result(reveal, move_address, host_id)?;
+fn outcome_day_index(format_id: &str, day: u64) -> Entry {
+    let anchor = Anchor { name: format!("outcomes {} day {}", format_id, day) };
+    Entry::App("anchor".into(), anchor.into())
+}
 
-    let entry = Entry::App("game_result".into(), game_result.into());
-    let address = hdk::commit_entry(&entry)?;
-    Ok(address)
+fn index_outcome(format_id: &str, outcome_address: &Address) -> ZomeApiResult<()> {
+    let day: u64 = timestamp_seconds(&get_header(outcome_address)?)? / SECONDS_PER_DAY;
+    let anchor_address: Address = hdk::commit_entry(&outcome_index(format_id))?;
+    let day_anchor_address: Address = hdk::commit_entry(&outcome_day_index(format_id, day))?;
+    if !hdk::get_links(&anchor_address, "outcome_day")?.addresses().contains(&day_anchor_address) {
+        hdk::link_entries(&anchor_address, &day_anchor_address, "outcome_day")?;
+    }
+    hdk::link_entries(&day_anchor_address, outcome_address, "outcome")?;
+    Ok(())
 }
 
-pub fn handle_get_offer(address: Address) -> ZomeApiResult<Offer> {
-    match hdk::get_entry(&address) {
-        Ok(Some(Entry::App(_, api_result))) => Ok(api_result.try_into()?),
-        _ => Err(String::from("No offer found").into())
+fn validate_outcome_link(validation_data: hdk::LinkValidationData) -> Result<(), String> {
+    if let hdk::LinkValidationData::LinkAdd{link, validation_data: _} = validation_data {
+        let target: &Address = link.link().target();
+        let outcome: Outcome = get_indexed_outcome(target)?;
+        let day: u64 = timestamp_seconds(&get_header(target)?)? / SECONDS_PER_DAY;
+        if *link.link().base() != hdk::entry_address(&outcome_day_index(outcome.format_id(), day))? {
+            return Err(String::from("Outcome is not indexed under its format and day"));
+        }
     }
+    Ok(())
 }
 
-pub fn handle_get_commitment(address: Address) -> ZomeApiResult<Commitment> {
-    match hdk::get_entry(&address) {
-        Ok(Some(Entry::App(_, api_result))) => Ok(api_result.try_into()?),
-        _ => Err(String::from("No commitment found").into())
+fn validate_outcome_day_link(validation_data: hdk::LinkValidationData) -> Result<(), String> {
+    if let hdk::LinkValidationData::LinkAdd{link, validation_data: _} = validation_data {
+        let anchor: Anchor = match hdk::get_entry(link.link().target()) {
+            Ok(Some(Entry::App(_, api_result))) => api_result.try_into()?,
+            _ => return Err(String::from("No anchor found")),
+        };
+        // "outcomes {format_id} day {day}"
+        let (format_id, day): (&str, Option<u64>) = match (anchor.name.find(' '), anchor.name.rfind(" day ")) {
+            (Some(start), Some(end)) if start < end => (&anchor.name[start + 1..end], anchor.name[end + 5..].parse::<u64>().ok()),
+            _ => return Err(String::from("Target is not an outcome day anchor")),
+        };
+        let day: u64 = match day {
+            Some(day) => day,
+            None => return Err(String::from("Target is not an outcome day anchor")),
+        };
+        if *link.link().target() != hdk::entry_address(&outcome_day_index(format_id, day))?
+            || *link.link().base() != hdk::entry_address(&outcome_index(format_id))? {
+            return Err(String::from("Day anchor is not linked from its format's outcome index"));
+        }
     }
+    Ok(())
 }
 
-pub fn handle_get_move(address: Address) -> ZomeApiResult<Move> {
-    match hdk::get_entry(&address) {
-        Ok(Some(Entry::App(_, api_result))) => Ok(api_result.try_into()?),
-        _ => Err(String::from("No move found").into())
+fn validate_rating_snapshot_link(validation_data: hdk::LinkValidationData) -> Result<(), String> {
+    if let hdk::LinkValidationData::LinkAdd{link, validation_data: _} = validation_data {
+        let snapshot: RatingSnapshot = handle_get_rating_snapshot(link.link().target().clone())?;
+        if *link.link().base() != hdk::entry_address(&rating_snapshot_index(&snapshot.format_id))? {
+            return Err(String::from("Snapshot is not indexed under its format"));
+        }
     }
+    Ok(())
 }
 
-pub fn handle_get_game_result(address: Address) -> ZomeApiResult<GameResult> {
-    match hdk::get_entry(&address) {
-        Ok(Some(Entry::App(_, api_result))) => Ok(api_result.try_into()?),
-        _ => Err(String::from("No game result found").into())
+// The day of a day anchor of the outcome index, None for any other anchor
+fn get_outcome_day(format_id: &str, anchor_address: &Address) -> ZomeApiResult<Option<u64>> {
+    let anchor: Anchor = match hdk::get_entry(anchor_address) {
+        Ok(Some(Entry::App(_, api_result))) => api_result.try_into()?,
+        _ => return Err(String::from("No anchor found").into()),
+    };
+    let prefix: String = format!("outcomes {} day ", format_id);
+    Ok(if anchor.name.starts_with(&prefix) { anchor.name[prefix.len()..].parse::<u64>().ok() } else { None })
+}
+
+fn get_indexed_outcome(address: &Address) -> ZomeApiResult<Outcome> {
+    match hdk::get_entry(address) {
+        Ok(Some(Entry::App(entry_type, api_result))) => match String::from(entry_type).as_ref() {
+            "game_result" => {
+                let game_result: GameResult = api_result.try_into()?;
+                Ok(game_result.into())
+            },
+            "forfeit_claim" => {
+                let claim: ForfeitClaim = api_result.try_into()?;
+                Ok(claim.into())
+            },
+            _ => Err(String::from("No outcome found").into()),
+        },
+        _ => Err(String::from("No outcome found").into()),
     }
 }
 
-// this is not the correct way to handle agent ids
-/*
-pub fn handle_get_agent_id(address: Address) -> ZomeApiResult<AgentId> {
-    if let Ok(Some(agent_id_entry)) = hdk::get_entry(&address) {
-        match agent_id_entry {
-            Entry::AgentId(agent_id) => return Ok(agent_id),
-            _ => Err(String::from("Get AgentId failure: No agent found").into())
+// (timestamp, address, outcome) for each game of the format that counts, in header timestamp order
+// Games with an upheld dispute do not count, and only the first outcome of a game does.
+// Only outcomes after the last one a snapshot covered are read, and only from its day on.
+fn get_rated_outcomes(format_id: &str, last_covered: Option<&(u64, String)>) -> ZomeApiResult<Vec<(u64, Address, Outcome)>> {
+    let first_day: u64 = last_covered.map_or(0, |(timestamp, _)| timestamp / SECONDS_PER_DAY);
+    let mut outcomes: Vec<(u64, Address, Outcome)> = Vec::new();
+    for day_anchor_address in hdk::get_links(&hdk::entry_address(&outcome_index(format_id))?, "outcome_day")?.addresses().iter() {
+        match get_outcome_day(format_id, day_anchor_address)? {
+            Some(day) if day >= first_day => (),
+            _ => continue,
+        }
+        for address in hdk::get_links(day_anchor_address, "outcome")?.addresses().iter() {
+            let timestamp: u64 = timestamp_seconds(&get_header(address)?)?;
+            if last_covered.map_or(false, |covered| (timestamp, String::from(address.clone())) <= *covered) {
+                continue;
+            }
+            let outcome: Outcome = get_indexed_outcome(address)?;
+            if outcome.format_id() != format_id || handle_is_game_disputed(outcome.move_address().clone())? {
+                continue;
+            }
+            // a forfeit claim superseded by a game result for the same game is not rated
+            if get_decided_outcome(outcome.move_address())?.map(|(decided, _)| decided) != Some(address.clone()) {
+                continue;
+            }
+            outcomes.push((timestamp, address.clone(), outcome));
+        }
+    }
+    // ties are broken by address, so every agent replays the same order
+    outcomes.sort_by(|(timestamp, address, _), (other_timestamp, other_address, _)| {
+        (timestamp, String::from(address.clone())).cmp(&(other_timestamp, String::from(other_address.clone())))
+    });
+
+    let mut move_addresses: Vec<Address> = Vec::new();
+    let mut rated_outcomes: Vec<(u64, Address, Outcome)> = Vec::new();
+    for (timestamp, address, outcome) in outcomes {
+        if !move_addresses.contains(outcome.move_address()) {
+            move_addresses.push(outcome.move_address().clone());
+            rated_outcomes.push((timestamp, address, outcome));
         }
-    } else {
-        Err(String::from("Get AgentId failure: No entry found at address").into())
     }
+    Ok(rated_outcomes)
 }
-*/
 
-define_zome! {
-    entries: [
-        define_offer_entry(),
-        define_commitment_entry(),
-        define_move_entry(),
-        define_game_result_entry()
-    ]
+fn timelock_policy() -> TimelockPolicy {
+    hdk::property("timelocks")
+        .ok()
+        .and_then(|property| serde_json::from_str(&String::from(property)).ok())
+        .unwrap_or_default()
+}
 
-    genesis: || { Ok(()) }
+fn rating_policy() -> RatingPolicy {
+    hdk::property("ratings")
+        .ok()
+        .and_then(|property| serde_json::from_str(&String::from(property)).ok())
+        .unwrap_or_default()
+}
 
-    functions: [
-        new_offer: {
-            inputs: |challenger_id_: Address, format_id_: String|,
-            outputs: |result: ZomeApiResult<Address>|,
-            handler: handle_new_offer
-        }
-        new_commitment: {
-            inputs: |component_: Component, offer_address_: Address, host_id_: Address, nonce_: String|,
-            outputs: |result: ZomeApiResult<Address>|,
-            handler: handle_new_commitment
+// (player, rank) for each player of an outcome, where rank 0 is first and equal ranks draw
+fn outcome_ranks(outcome: &Outcome) -> Vec<(Address, usize)> {
+    match outcome {
+        Outcome::Win { winner_id, loser_id, .. } => vec![(winner_id.clone(), 0), (loser_id.clone(), 1)],
+        Outcome::Draw { players, .. } => players.iter().map(|player| (player.clone(), 0)).collect(),
+    }
+}
+
+impl Standing {
+    fn new(agent_id: Address, rating: PlayerRating) -> Standing {
+        Standing {
+            agent_id,
+            rating: rating.rating,
+            deviation: rating.deviation,
+            volatility: rating.volatility,
+            games: rating.games,
+            last_played: rating.last_played,
+            wins: 0,
+            draws: 0,
+            losses: 0,
         }
-        new_move: {
-            inputs: |component_: Component, commitment_address_: Address, challenger_id_: Address|,
-            outputs: |result: ZomeApiResult<Address>|,
-            handler: handle_new_move
+    }
+
+    fn player_rating(&self) -> PlayerRating {
+        PlayerRating {
+            rating: self.rating,
+            deviation: self.deviation,
+            volatility: self.volatility,
+            games: self.games,
+            last_played: self.last_played,
         }
-        new_game_result: {
-            inputs: |reveal: Reveal, move_address: Address, host_id: Address|,
-            outputs: |result: ZomeApiResult<Address>|,
-            handler: handle_new_game_result
+    }
+}
+
+// Rates the outcomes in order on top of the standings, returning (agent, change) for each game
+fn rate_outcomes(system: &dyn RatingSystem, standings: &mut BTreeMap<Address, Standing>, outcomes: &[(u64, Address, Outcome)]) -> Vec<(Address, RatingChange)> {
+    let mut history: Vec<(Address, RatingChange)> = Vec::new();
+    for (timestamp, _, outcome) in outcomes.iter() {
+        let players: Vec<(Address, usize)> = outcome_ranks(outcome);
+        if players.len() < 2 {
+            continue;
         }
-        get_offer: {
-            inputs: |address: Address|,
-            outputs: |result: ZomeApiResult<Offer>|,
-            handler: handle_get_offer
+        // every player is their own team in roshambo
+        let teams: Vec<Vec<PlayerRating>> = players
+            .iter()
+            .map(|(player, _)| vec![standings.get(player).map(|standing| standing.player_rating()).unwrap_or_else(|| system.initial())])
+            .collect();
+        let ranks: Vec<usize> = players.iter().map(|(_, rank)| *rank).collect();
+        let rated: Vec<Vec<PlayerRating>> = system.rate(&teams, &ranks, *timestamp);
+
+        for (index, (agent_id, rank)) in players.iter().enumerate() {
+            let (before, after) = (&teams[index][0], &rated[index][0]);
+            let opponents: Vec<&(Address, usize)> = players.iter().filter(|(player, _)| player != agent_id).collect();
+            let points: f64 = opponents
+                .iter()
+                .map(|(_, other_rank)| if rank < other_rank { 1.0 } else if rank == other_rank { 0.5 } else { 0.0 })
+                .sum();
+            let score: f64 = points / opponents.len() as f64;
+            history.push((agent_id.clone(), RatingChange {
+                move_address: outcome.move_address().clone(),
+                opponent_ids: opponents.iter().map(|(player, _)| player.clone()).collect(),
+                score: score,
+                rating: after.rating,
+                deviation: after.deviation,
+                change: after.rating - before.rating,
+                timestamp: *timestamp,
+            }));
+
+            let (wins, draws, losses) = match standings.get(agent_id) {
+                Some(standing) => (standing.wins, standing.draws, standing.losses),
+                None => (0, 0, 0),
+            };
+            standings.insert(agent_id.clone(), Standing {
+                wins: wins + if score > 0.5 { 1 } else { 0 },
+                draws: draws + if score == 0.5 { 1 } else { 0 },
+                losses: losses + if score < 0.5 { 1 } else { 0 },
+                ..Standing::new(agent_id.clone(), after.clone())
+            });
         }
-        get_commitment: {
-            inputs: |address: Address|,
-            outputs: |result: ZomeApiResult<Commitment>|,
-            handler: handle_get_commitment
+    }
+    history
+}
+
+// Standings of every agent who played the format, and (agent, change) for each game replayed
+// From a snapshot, only the outcomes it does not cover are replayed, so the history only has those
+fn replay_ratings(format_id: &str, system: &dyn RatingSystem, from_snapshot: bool) -> ZomeApiResult<(BTreeMap<Address, Standing>, Vec<(Address, RatingChange)>)> {
+    let mut start: SnapshotChain = SnapshotChain::default();
+    if from_snapshot {
+        if let Some(snapshot_address) = handle_get_latest_rating_snapshot(format_id.to_string())? {
+            start = get_snapshot_chain(&snapshot_address)?;
         }
-        get_move: {
-            inputs: |address: Address|,
-            outputs: |result: ZomeApiResult<Move>|,
-            handler: handle_get_move
+    }
+    let mut standings: BTreeMap<Address, Standing> = start.standings;
+    let outcomes: Vec<(u64, Address, Outcome)> = get_rated_outcomes(format_id, start.last_covered.as_ref())?;
+    let history: Vec<(Address, RatingChange)> = rate_outcomes(system, &mut standings, &outcomes);
+    Ok((standings, history))
+}
+
+fn rating_snapshot_index(format_id: &str) -> Entry {
+    let anchor = Anchor { name: format!("rating snapshots {}", format_id) };
+    Entry::App("anchor".into(), anchor.into())
+}
+
+// Each snapshot chains the hash of the previous one with the outcomes it adds
+fn snapshot_hash(previous_hash: Option<&HashString>, outcome_addresses: &[Address]) -> ZomeApiResult<HashString> {
+    let addresses: Vec<String> = outcome_addresses.iter().map(|address| String::from(address.clone())).collect();
+    let preimage: Vec<u8> = canonical::Encoder::new("roshambo-rating-snapshot-v1")
+        .optional(previous_hash.map(|hash| String::from(hash.clone())))
+        .list(&addresses)
+        .finish();
+    calculate_hash(&preimage, &HashAlgorithm::Sha2256)
+}
+
+// What a chain of snapshots adds up to
+#[derive(Default)]
+struct SnapshotChain {
+    standings: BTreeMap<Address, Standing>,
+    last_covered: Option<(u64, String)>, // (timestamp, address) of the last outcome covered, in replay order
+    outcomes_hash: Option<HashString>,
+    outcome_count: u64,
+}
+
+// Each snapshot covers every rated outcome up to its last one, so only the latest snapshot needs reading
+fn get_snapshot_chain(snapshot_address: &Address) -> ZomeApiResult<SnapshotChain> {
+    let snapshot: RatingSnapshot = handle_get_rating_snapshot(snapshot_address.clone())?;
+    let last_covered: Option<(u64, String)> = match snapshot.outcome_addresses.last() {
+        Some(address) => Some((timestamp_seconds(&get_header(address)?)?, String::from(address.clone()))),
+        None => None,
+    };
+    Ok(SnapshotChain {
+        standings: snapshot.standings.into_iter().map(|standing| (standing.agent_id.clone(), standing)).collect(),
+        last_covered: last_covered,
+        outcomes_hash: Some(snapshot.outcomes_hash),
+        outcome_count: snapshot.outcome_count,
+    })
+}
+
+// Recomputes the snapshot from the previous one and the outcomes it lists
+fn validate_rating_snapshot(snapshot: RatingSnapshot) -> Result<(), String> {
+    let start: SnapshotChain = match &snapshot.previous_snapshot_address {
+        Some(address) => {
+            if handle_get_rating_snapshot(address.clone())?.format_id != snapshot.format_id {
+                return Err(String::from("Previous snapshot is of another format"));
+            }
+            get_snapshot_chain(address)?
+        },
+        None => SnapshotChain::default(),
+    };
+    if snapshot.outcome_addresses.is_empty() {
+        return Err(String::from("Snapshot does not cover any new outcomes"));
+    }
+    // only the listed outcomes are replayed, so the snapshot validates the same way whatever links or disputes arrive later.
+    // They must be outcomes of the format after the previous snapshot's last one, in replay order, one per game.
+    let mut outcomes: Vec<(u64, Address, Outcome)> = Vec::new();
+    let mut last: Option<(u64, String)> = start.last_covered.clone();
+    for address in snapshot.outcome_addresses.iter() {
+        let outcome: Outcome = get_indexed_outcome(address)?;
+        let key: (u64, String) = (timestamp_seconds(&get_header(address)?)?, String::from(address.clone()));
+        if outcome.format_id() != snapshot.format_id {
+            return Err(String::from("Snapshot outcome is of another format"));
         }
-        get_game_result: {
-            inputs: |address: Address|,
-            outputs: |result: ZomeApiResult<GameResult>|,
-            handler: handle_get_game_result
+        if last.as_ref().map_or(false, |last| key <= *last) {
+            return Err(String::from("Snapshot outcomes are not after the previous snapshot in replay order"));
         }
-        /*
-        get_agent_id: {
-            inputs: |address: Address|,
-            outputs: |result: ZomeApiResult<AgentId>|,
-            handler: handle_get_agent_id
+        if outcomes.iter().any(|(_, _, other)| other.move_address() == outcome.move_address()) {
+            return Err(String::from("Snapshot lists more than one outcome of a game"));
         }
-        */
-    ]
+        outcomes.push((key.0, address.clone(), outcome));
+        last = Some(key);
+    }
+    let addresses: Vec<Address> = snapshot.outcome_addresses.clone();
+    if snapshot.outcomes_hash != snapshot_hash(start.outcomes_hash.as_ref(), &addresses)? {
+        return Err(String::from("Snapshot outcomes hash does not match its outcomes"));
+    }
+    if snapshot.outcome_count != start.outcome_count + addresses.len() as u64 {
+        return Err(String::from("Snapshot outcome count does not match its outcomes"));
+    }
+    let mut standings: BTreeMap<Address, Standing> = start.standings;
+    rate_outcomes(rating_policy().system(&snapshot.format_id).as_ref(), &mut standings, &outcomes);
+    if snapshot.standings != standings.into_iter().map(|(_, standing)| standing).collect::<Vec<Standing>>() {
+        return Err(String::from("Snapshot standings do not match the replayed outcomes"));
+    }
+    Ok(())
+}
 
-    traits: {
-        hc_public [
-            new_offer,
-            get_offer,
-            new_commitment,
-            get_commitment,
-            new_move,
-            get_move,
-            new_game_result,
-            get_game_result
-            // get_agent_id
-        ]
+fn player_index() -> Entry {
+    let anchor = Anchor { name: String::from("players") };
+    Entry::App("anchor".into(), anchor.into())
+}
+
+// The agents who have played a format, for its leaderboard
+fn format_player_index(format_id: &str) -> Entry {
+    let anchor = Anchor { name: format!("players {}", format_id) };
+    Entry::App("anchor".into(), anchor.into())
+}
+
+fn validate_player_link(validation_data: hdk::LinkValidationData) -> Result<(), String> {
+    if let hdk::LinkValidationData::LinkAdd{link, validation_data: validation_} = validation_data {
+        if author_from_header(&validation_.package.chain_header)? != *link.link().target() {
+            return Err(String::from("Link author is not the linked agent."));
+        }
+        let anchor: Anchor = match hdk::get_entry(link.link().base()) {
+            Ok(Some(Entry::App(_, api_result))) => api_result.try_into()?,
+            _ => return Err(String::from("No anchor found")),
+        };
+        if anchor.name != "players" && !anchor.name.starts_with("players ") {
+            return Err(String::from("Agents are only linked from player indexes"));
+        }
     }
+    Ok(())
 }
 
-// Private helper functions
+// Links this agent from the player index, which jurors are drawn from, and from the format's player index
+fn join_player_index(format_id: &str) -> ZomeApiResult<()> {
+    for index in vec![player_index(), format_player_index(format_id)] {
+        let anchor_address: Address = hdk::commit_entry(&index)?;
+        if !hdk::get_links(&anchor_address, "player")?.addresses().contains(&hdk::AGENT_ADDRESS) {
+            hdk::link_entries(&anchor_address, &hdk::AGENT_ADDRESS, "player")?;
+        }
+    }
+    Ok(())
+}
 
-fn calculate_hash<T: Into<JsonString>>(raw_data: T) -> HashString {
-    HashString::encode_from_json_string(raw_data.into(), Multihash::SHA2256)
+// Every indexed agent but the players, sorted, recorded in the dispute so its jury never changes
+fn get_juror_pool(subject_address: &Address) -> ZomeApiResult<Vec<Address>> {
+    let (host_id, challenger_id) = get_dispute_players(subject_address)?;
+    let mut juror_pool: Vec<Address> = hdk::get_links(&hdk::entry_address(&player_index())?, "player")?
+        .addresses()
+        .iter()
+        .filter(|agent| **agent != host_id && **agent != challenger_id)
+        .cloned()
+        .collect();
+    juror_pool.sort();
+    juror_pool.dedup();
+    Ok(juror_pool)
 }
 
-fn get_author(entry_address: &Address) -> ZomeApiResult<Address> {
-    if let GetEntryResultType::Single(result) = hdk::get_entry_result(
-        entry_address,
-        GetEntryOptions {
-            entry: false,
-            headers: true,
-            ..Default::default()
-        },
-    )?
-    .result
-    {
-        let author_address = result
-            .headers
-            .into_iter()
-            .map(|header| author_from_header(&header).unwrap())
-            .next()
-            .unwrap();
-        // this is not the correct way of handling agent ids
-        // let agent_id: AgentId = handle_get_agent_id(author_address)?;
-        return Ok(author_address);
-    } else {
-        unimplemented!()
+// Ranks the dispute's juror pool by a hash seeded with the disputed entry, so anyone can recompute the jury
+fn select_jurors(dispute: &Dispute) -> ZomeApiResult<Vec<Address>> {
+    let mut ranked: Vec<(String, Address)> = Vec::new();
+    for agent in dispute.juror_pool.iter() {
+        let seed: Vec<u8> = canonical::Encoder::new("roshambo-juror-v1")
+            .string(&String::from(dispute.subject_address.clone()))
+            .string(&String::from(agent.clone()))
+            .finish();
+        ranked.push((String::from(calculate_hash(&seed, &HashAlgorithm::Sha2256)?), agent.clone()));
     }
+    ranked.sort();
+    Ok(ranked.into_iter().take(JURY_SIZE).map(|(_, agent)| agent).collect())
 }
 
-fn author_from_header(chain_header: &ChainHeader) -> ZomeApiResult<Address> {
-    let author_address = chain_header.provenances()
-        .first()
-        .unwrap()
-        .clone()
-        .source();
-    return Ok(author_address)
+// A vote is bound to its jury and juror, like a reveal is bound to its offer and challenger
+fn vote_preimage(vote: &JuryVote, nonce: &str, jury_address: &Address, juror: &Address) -> Vec<u8> {
+    canonical::Encoder::new("roshambo-vote-v1")
+        .string(vote.name())
+        .string(nonce)
+        .string(&String::from(jury_address.clone()))
+        .string(&String::from(juror.clone()))
+        .finish()
 }
 
-fn create_game_result(reveal_: Reveal, move_address_: Address, host_id: Address) -> ZomeApiResult<GameResult> {
-    let move_: Move = handle_get_move(move_address_.clone())?;
-    let host_component: &Component = &move_.component;
-    let challenger_component: &Component = &reveal_.component;
-    let format_id_ = move_.format_id.clone();
-    let challenger_id = move_.challenger_id;
-    let winner: String = resolve_components(host_component, challenger_component);
+fn jury_vote_verdict(vote: &JuryVote) -> Verdict {
+    match vote {
+        JuryVote::Uphold => Verdict::Upheld { finding: String::from("Upheld by jury majority") },
+        JuryVote::Reject => Verdict::Rejected,
+    }
+}
 
-    if winner == String::from("host") {
-        Ok(GameResult::Win {
-            reveal: reveal_,
-            move_address: move_address_,
-            winner_id: host_id,
-            loser_id: challenger_id,
-            format_id: format_id_,
-        })
-    } else if winner == String::from("challenger") {
-        Ok(GameResult::Win {
-            reveal: reveal_,
-            move_address: move_address_,
-            winner_id: challenger_id,
-            loser_id: host_id,
-            format_id: format_id_,
-        })
-    } else if winner == String::from("draw") {
-        Ok(GameResult::Draw {
-            reveal: reveal_,
-            move_address: move_address_,
-            players: vec![host_id, challenger_id],
-            format_id: format_id_,
-        })
-    } else {
-        unimplemented!();
+fn validate_jury(jury: Jury) -> Result<(), String> {
+    let dispute: Dispute = handle_get_dispute(jury.dispute_address.clone())?;
+    if dispute.verdict != Verdict::Inconclusive {
+        return Err(String::from("Only inconclusive disputes go to a jury"));
     }
+    if jury.jurors.is_empty() {
+        return Err(String::from("No agents in the player index can serve as jurors"));
+    }
+    if jury.jurors != select_jurors(&dispute)? {
+        return Err(String::from("Jurors do not match the selection from the juror pool"));
+    }
+    Ok(())
 }
 
-fn resolve_components(host_component: &Component, challenger_component: &Component) -> String {
-    if host_component.wins_against.contains(&challenger_component.name) {
-        return String::from("host");
+fn validate_vote_commitment(vote_commitment: VoteCommitment, juror: Address) -> Result<(), String> {
+    let jury: Jury = handle_get_jury(vote_commitment.jury_address.clone())?;
+    if !jury.jurors.contains(&juror) {
+        return Err(String::from("Vote commitment author is not a juror."));
     }
-    if challenger_component.wins_against.contains(&host_component.name) {
-        return String::from("challenger");
+    for address in hdk::get_links(&vote_commitment.jury_address, "vote_commitment")?.addresses().iter() {
+        if get_author(address)? == juror {
+            return Err(String::from("Juror has already committed a vote"));
+        }
     }
-    if host_component.loses_against.contains(&challenger_component.name) {
-        return String::from("challenger");
+    Ok(())
+}
+
+fn validate_vote_reveal(vote_reveal: VoteReveal, juror: Address) -> Result<(), String> {
+    let vote_commitment: VoteCommitment = handle_get_vote_commitment(vote_reveal.vote_commitment_address.clone())?;
+    let jury: Jury = handle_get_jury(vote_commitment.jury_address.clone())?;
+
+    if get_author(&vote_reveal.vote_commitment_address)? != juror {
+        return Err(String::from("Vote reveal author does not match vote commitment author."));
     }
-    if challenger_component.loses_against.contains(&host_component.name) {
-        return String::from("host");
+    // nobody reveals before every juror has committed, so no vote can follow the others
+    if get_vote_commitments(&vote_commitment.jury_address, &jury)?.len() < jury.jurors.len() {
+        return Err(String::from("Not every juror has committed a vote"));
     }
-    return String::from("draw");
+    validate_nonce(&vote_reveal.nonce)?;
+    if vote_commitment.hash != calculate_hash(&vote_preimage(&vote_reveal.vote, &vote_reveal.nonce, &vote_commitment.jury_address, &juror), &HashAlgorithm::Sha2256)? {
+        return Err(String::from("Vote commitment hash does not match hash of vote"));
+    }
+    Ok(())
 }
 
-fn validate_game_result(game_result: GameResult, reveal: Reveal, move_address: Address, result_author_address: Address) -> Result<(), String> {
-    let move_author: Address = get_author(&move_address)?;
-    let move_: Move = handle_get_move(move_address.clone())?;
+// (address, juror) of the first vote commitment of each juror to this jury
+fn get_vote_commitments(jury_address: &Address, jury: &Jury) -> ZomeApiResult<Vec<(Address, Address)>> {
+    let mut vote_commitments: Vec<(Address, Address)> = Vec::new();
+    for address in hdk::get_links(jury_address, "vote_commitment")?.addresses().iter() {
+        let juror: Address = get_author(address)?;
+        if handle_get_vote_commitment(address.clone())?.jury_address == *jury_address
+            && jury.jurors.contains(&juror)
+            && !vote_commitments.iter().any(|(_, counted)| *counted == juror) {
+            vote_commitments.push((address.clone(), juror));
+        }
+    }
+    Ok(vote_commitments)
+}
 
-    if result_author_address != move_.challenger_id.clone() {
-        return Err(String::from("Game result author does not match challenger id."));
+// A jury, vote commitment, vote reveal or jury verdict may only be linked from the entry it belongs to, by its own author
+fn validate_jury_link(validation_data: hdk::LinkValidationData) -> Result<(), String> {
+    if let hdk::LinkValidationData::LinkAdd{link, validation_data: validation_} = validation_data {
+        let link_author_address: Address = author_from_header(&validation_.package.chain_header)?;
+        let base: &Address = link.link().base();
+        let target: &Address = link.link().target();
+        let owner_address: Address = match hdk::get_entry(target) {
+            Ok(Some(Entry::App(entry_type, api_result))) => match String::from(entry_type).as_ref() {
+                "jury" => {
+                    let jury: Jury = api_result.try_into()?;
+                    jury.dispute_address
+                },
+                "vote_commitment" => {
+                    let vote_commitment: VoteCommitment = api_result.try_into()?;
+                    vote_commitment.jury_address
+                },
+                "vote_reveal" => {
+                    let vote_reveal: VoteReveal = api_result.try_into()?;
+                    vote_reveal.vote_commitment_address
+                },
+                "jury_verdict" => {
+                    let jury_verdict: JuryVerdict = api_result.try_into()?;
+                    jury_verdict.dispute_address
+                },
+                _ => return Err(String::from("Link target is not part of a jury")),
+            },
+            _ => return Err(String::from("Link target not found")),
+        };
+        if owner_address != *base {
+            return Err(String::from("Link target belongs to another entry"));
+        }
+        if get_author(target)? != link_author_address {
+            return Err(String::from("Link author is not the target's author."));
+        }
     }
-    if move_.hash != calculate_hash(reveal.clone()) {
-        return Err(String::from("Move hash does not match hash of reveal"));
+    Ok(())
+}
+
+fn validate_jury_verdict(jury_verdict: JuryVerdict) -> Result<(), String> {
+    let jury: Jury = handle_get_jury(jury_verdict.jury_address.clone())?;
+    if jury.dispute_address != jury_verdict.dispute_address {
+        return Err(String::from("Jury does not decide this dispute"));
     }
-    if game_result != create_game_result(reveal, move_address, move_author)? {
-        return Err(String::from("Game results do not match"));
+    let mut voters: Vec<Address> = Vec::new();
+    for address in jury_verdict.vote_reveal_addresses.iter() {
+        let vote_reveal: VoteReveal = handle_get_vote_reveal(address.clone())?;
+        let vote_commitment: VoteCommitment = handle_get_vote_commitment(vote_reveal.vote_commitment_address.clone())?;
+        let juror: Address = get_author(address)?;
+        if vote_commitment.jury_address != jury_verdict.jury_address || voters.contains(&juror) {
+            return Err(String::from("Vote reveals are not from distinct jurors of the jury"));
+        }
+        if jury_vote_verdict(&vote_reveal.vote) != jury_verdict.verdict {
+            return Err(String::from("Vote reveal does not match the verdict"));
+        }
+        voters.push(juror);
+    }
+    if voters.len() < jury.jurors.len() / 2 + 1 {
+        return Err(String::from("Verdict does not have a majority of jurors"));
     }
-    // TODO Assert!(reveal component is in the format);
     Ok(())
 }
 
-/* We've decided this can be handled client-side/off-chain.
-fn generate_nonce() -> String {
-    thread_rng() // TODO research cryptographic security of this RNG
-        .sample_iter(&Alphanumeric)
-        .take(24)
-        .collect()
+// Signatures are deterministic, so the agent can re-derive this nonce at any time but nobody else can
+fn derive_nonce(offer_address: &Address, nonce_counter: u64) -> ZomeApiResult<String> {
+    let signature: String = hdk::sign(format!("roshambo nonce {} {}", offer_address, nonce_counter))?;
+    Ok(signature.chars().filter(|character| character.is_ascii_alphanumeric()).collect())
 }
-*/
\ No newline at end of file
+
+// Takes the next counter from the latest nonce counter on this agent's chain and stores the one after it
+// Query walks the chain from its top, so one entry is read however long the chain is.
+fn next_nonce_counter() -> ZomeApiResult<u64> {
+    let nonce_counter: u64 = match hdk::query("nonce_counter".into(), 0, 1)?.first() {
+        Some(address) => match hdk::get_entry(address) {
+            Ok(Some(Entry::App(_, api_result))) => {
+                let stored: NonceCounter = api_result.try_into()?;
+                stored.next
+            },
+            _ => return Err(String::from("No nonce counter found").into()),
+        },
+        None => 0,
+    };
+    let entry = Entry::App("nonce_counter".into(), NonceCounter { next: nonce_counter + 1 }.into());
+    hdk::commit_entry(&entry)?;
+    Ok(nonce_counter)
+}
\ No newline at end of file
@@ -0,0 +1,70 @@
+// Binary Merkle trees over already-hashed leaves, generic over the hash type and the node hash.
+//
+// Each level pairs neighbouring hashes left to right; a level with an odd count pairs its last
+// hash with itself. A proof lists the sibling hash at each level from the leaf up to the root,
+// and the leaf's index decides at each level whether the running hash is the left or right child.
+
+// Levels of a tree with this many leaves, not counting the leaves themselves
+pub fn depth(leaf_count: usize) -> usize {
+    let mut width: usize = leaf_count;
+    let mut depth: usize = 0;
+    while width > 1 {
+        width = (width + 1) / 2;
+        depth += 1;
+    }
+    depth
+}
+
+pub fn root<T: Clone, E>(leaves: &[T], node: &dyn Fn(&T, &T) -> Result<T, E>) -> Result<Option<T>, E> {
+    let mut level: Vec<T> = leaves.to_vec();
+    while level.len() > 1 {
+        level = parent_level(&level, node)?;
+    }
+    Ok(level.pop())
+}
+
+pub fn proof<T: Clone, E>(leaves: &[T], index: usize, node: &dyn Fn(&T, &T) -> Result<T, E>) -> Result<Option<Vec<T>>, E> {
+    if index >= leaves.len() {
+        return Ok(None);
+    }
+    let mut level: Vec<T> = leaves.to_vec();
+    let mut index: usize = index;
+    let mut siblings: Vec<T> = Vec::new();
+    while level.len() > 1 {
+        let sibling: usize = if index % 2 == 0 { (index + 1).min(level.len() - 1) } else { index - 1 };
+        siblings.push(level[sibling].clone());
+        level = parent_level(&level, node)?;
+        index /= 2;
+    }
+    Ok(Some(siblings))
+}
+
+// Whether proof shows leaf at index in a tree of leaf_count leaves with this root
+pub fn verify<T: Clone + PartialEq, E>(
+    leaf: &T,
+    index: usize,
+    leaf_count: usize,
+    proof: &[T],
+    root: &T,
+    node: &dyn Fn(&T, &T) -> Result<T, E>,
+) -> Result<bool, E> {
+    if index >= leaf_count || proof.len() != depth(leaf_count) {
+        return Ok(false);
+    }
+    let mut hash: T = leaf.clone();
+    let mut index: usize = index;
+    for sibling in proof {
+        hash = if index % 2 == 0 { node(&hash, sibling)? } else { node(sibling, &hash)? };
+        index /= 2;
+    }
+    Ok(hash == *root)
+}
+
+fn parent_level<T: Clone, E>(level: &[T], node: &dyn Fn(&T, &T) -> Result<T, E>) -> Result<Vec<T>, E> {
+    let mut parents: Vec<T> = Vec::new();
+    for pair in level.chunks(2) {
+        let right: &T = pair.last().unwrap_or(&pair[0]);
+        parents.push(node(&pair[0], right)?);
+    }
+    Ok(parents)
+}
@@ -0,0 +1,435 @@
+// Rating systems
+// A rating system replays a stream of games, each between teams of one or more players ranked
+// from first (rank 0) down, with equal ranks drawing. Every system keeps a rating and how uncertain
+// it is, so readers can tell an established player from one with few or long-ago games.
+//
+// Elo: a game moves each rating by k * (score - expected score), where the score is 1 for a win,
+// 0.5 for a draw and 0 for a loss. Players are provisional for their first games: their own rating
+// moves with a larger k, and their opponents' ratings only move in proportion to how established
+// the provisional player already is. Elo has no deviation.
+//
+// Glicko-2: see http://www.glicko.net/glicko/glicko2.pdf. Every game is its own rating period, and
+// a player's deviation grows back towards its initial value for each period they were inactive.
+//
+// TrueSkill: see https://www.microsoft.com/en-us/research/publication/trueskilltm-a-bayesian-skill-rating-system/
+// Teams are the sum of their players. Games of more than two teams are approximated by updating
+// each pair of neighbouring teams in the ranking, rather than by iterating the full factor graph.
+
+use std::collections::BTreeMap;
+use std::f64::consts::PI;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerRating {
+    pub rating: f64,
+    pub deviation: f64,
+    pub volatility: f64, // Glicko-2 only
+    pub games: u64,
+    pub last_played: Option<u64>, // header timestamp of the player's latest game
+}
+
+pub trait RatingSystem {
+    fn initial(&self) -> PlayerRating;
+
+    // The ratings of each team's players after a game, with teams in the same order as their ranks
+    fn rate(&self, teams: &[Vec<PlayerRating>], ranks: &[usize], timestamp: u64) -> Vec<Vec<PlayerRating>>;
+
+    fn is_provisional(&self, player: &PlayerRating) -> bool;
+
+    // What leaderboards rank players by
+    fn score(&self, player: &PlayerRating) -> f64 {
+        player.rating
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum RatingSystemName {
+    Elo,
+    Glicko2,
+    TrueSkill,
+}
+
+// DNA property "ratings" picks a rating system per format and configures each system, e.g.
+// {"system": "Glicko2", "formats": {"teams": "TrueSkill"}, "elo": {"k_factor": 24}, "glicko2": {"tau": 0.3}}
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct RatingPolicy {
+    system: Option<RatingSystemName>,
+    formats: Option<BTreeMap<String, RatingSystemName>>,
+    elo: Option<EloConfig>,
+    glicko2: Option<Glicko2Config>,
+    trueskill: Option<TrueSkillConfig>,
+}
+
+impl RatingPolicy {
+    pub fn system(&self, format_id: &str) -> Box<dyn RatingSystem> {
+        let name: RatingSystemName = self.formats
+            .as_ref()
+            .and_then(|formats| formats.get(format_id).cloned())
+            .or_else(|| self.system.clone())
+            .unwrap_or(RatingSystemName::Elo);
+        match name {
+            RatingSystemName::Elo => Box::new(self.elo.clone().unwrap_or_default()),
+            RatingSystemName::Glicko2 => Box::new(self.glicko2.clone().unwrap_or_default()),
+            RatingSystemName::TrueSkill => Box::new(self.trueskill.clone().unwrap_or_default()),
+        }
+    }
+}
+
+// 1 for beating the other rank, 0.5 for drawing and 0 for losing
+fn rank_score(rank: usize, other_rank: usize) -> f64 {
+    if rank < other_rank {
+        1.0
+    } else if rank == other_rank {
+        0.5
+    } else {
+        0.0
+    }
+}
+
+fn average(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+fn played(player: &PlayerRating, rating: f64, deviation: f64, volatility: f64, timestamp: u64) -> PlayerRating {
+    PlayerRating {
+        rating,
+        deviation,
+        volatility,
+        games: player.games + 1,
+        last_played: Some(timestamp),
+    }
+}
+
+// Elo
+
+const ELO_INITIAL_RATING: f64 = 1500.0;
+const ELO_K_FACTOR: f64 = 32.0;
+const ELO_PROVISIONAL_K_FACTOR: f64 = 64.0;
+const ELO_PROVISIONAL_GAMES: u64 = 10;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct EloConfig {
+    initial_rating: Option<f64>,
+    k_factor: Option<f64>,
+    provisional_k_factor: Option<f64>,
+    provisional_games: Option<u64>,
+}
+
+impl EloConfig {
+    // k for a player against opponents whose least established player has opponent_games games
+    fn k(&self, player: &PlayerRating, opponent_games: u64) -> f64 {
+        let provisional_games: u64 = self.provisional_games.unwrap_or(ELO_PROVISIONAL_GAMES);
+        if self.is_provisional(player) {
+            self.provisional_k_factor.unwrap_or(ELO_PROVISIONAL_K_FACTOR)
+        } else if opponent_games < provisional_games {
+            self.k_factor.unwrap_or(ELO_K_FACTOR) * opponent_games as f64 / provisional_games as f64
+        } else {
+            self.k_factor.unwrap_or(ELO_K_FACTOR)
+        }
+    }
+}
+
+impl RatingSystem for EloConfig {
+    fn initial(&self) -> PlayerRating {
+        PlayerRating {
+            rating: self.initial_rating.unwrap_or(ELO_INITIAL_RATING),
+            deviation: 0.0,
+            volatility: 0.0,
+            games: 0,
+            last_played: None,
+        }
+    }
+
+    // each team plays every other team at its average rating, and the results are averaged
+    fn rate(&self, teams: &[Vec<PlayerRating>], ranks: &[usize], timestamp: u64) -> Vec<Vec<PlayerRating>> {
+        let team_ratings: Vec<f64> = teams
+            .iter()
+            .map(|team| average(&team.iter().map(|player| player.rating).collect::<Vec<f64>>()))
+            .collect();
+        let opponents: f64 = (teams.len() - 1) as f64;
+        teams
+            .iter()
+            .enumerate()
+            .map(|(index, team)| {
+                let mut surprise: f64 = 0.0;
+                let mut opponent_games: u64 = u64::max_value();
+                for (other_index, other_team) in teams.iter().enumerate().filter(|(other_index, _)| *other_index != index) {
+                    let expected: f64 = 1.0 / (1.0 + 10f64.powf((team_ratings[other_index] - team_ratings[index]) / 400.0));
+                    surprise += rank_score(ranks[index], ranks[other_index]) - expected;
+                    opponent_games = other_team.iter().map(|player| player.games).fold(opponent_games, u64::min);
+                }
+                team.iter()
+                    .map(|player| {
+                        let rating: f64 = player.rating + self.k(player, opponent_games) * surprise / opponents;
+                        played(player, rating, 0.0, 0.0, timestamp)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn is_provisional(&self, player: &PlayerRating) -> bool {
+        player.games < self.provisional_games.unwrap_or(ELO_PROVISIONAL_GAMES)
+    }
+}
+
+// Glicko-2
+
+const GLICKO_SCALE: f64 = 173.7178;
+const GLICKO_INITIAL_RATING: f64 = 1500.0;
+const GLICKO_INITIAL_DEVIATION: f64 = 350.0;
+const GLICKO_INITIAL_VOLATILITY: f64 = 0.06;
+const GLICKO_TAU: f64 = 0.5;
+const GLICKO_RATING_PERIOD: u64 = 7 * 24 * 3600;
+const GLICKO_PROVISIONAL_DEVIATION: f64 = 110.0;
+const GLICKO_CONVERGENCE: f64 = 0.000001;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Glicko2Config {
+    initial_rating: Option<f64>,
+    initial_deviation: Option<f64>,
+    initial_volatility: Option<f64>,
+    tau: Option<f64>,                   // how much volatility may change
+    rating_period: Option<u64>,         // seconds of inactivity that grow the deviation by one period
+    provisional_deviation: Option<f64>, // players above this deviation are provisional
+}
+
+impl Glicko2Config {
+    // the player's deviation on the Glicko-2 scale, grown for each rating period since their last game
+    fn decayed_phi(&self, player: &PlayerRating, timestamp: u64) -> f64 {
+        let phi: f64 = player.deviation / GLICKO_SCALE;
+        let periods: f64 = match player.last_played {
+            Some(last_played) if timestamp > last_played => {
+                (timestamp - last_played) as f64 / self.rating_period.unwrap_or(GLICKO_RATING_PERIOD) as f64
+            },
+            _ => 0.0,
+        };
+        let initial_phi: f64 = self.initial_deviation.unwrap_or(GLICKO_INITIAL_DEVIATION) / GLICKO_SCALE;
+        (phi * phi + periods * player.volatility * player.volatility).sqrt().min(initial_phi)
+    }
+
+    // step 5 of the paper, by the Illinois algorithm
+    fn new_volatility(&self, phi: f64, volatility: f64, variance: f64, delta: f64) -> f64 {
+        let tau: f64 = self.tau.unwrap_or(GLICKO_TAU);
+        let a: f64 = (volatility * volatility).ln();
+        let f = |x: f64| {
+            let ex: f64 = x.exp();
+            ex * (delta * delta - phi * phi - variance - ex) / (2.0 * (phi * phi + variance + ex).powi(2)) - (x - a) / (tau * tau)
+        };
+        let mut lower: f64 = a;
+        let mut upper: f64 = if delta * delta > phi * phi + variance {
+            (delta * delta - phi * phi - variance).ln()
+        } else {
+            let mut k: f64 = 1.0;
+            while f(a - k * tau) < 0.0 {
+                k += 1.0;
+            }
+            a - k * tau
+        };
+        let (mut f_lower, mut f_upper) = (f(lower), f(upper));
+        while (upper - lower).abs() > GLICKO_CONVERGENCE {
+            let next: f64 = lower + (lower - upper) * f_lower / (f_upper - f_lower);
+            let f_next: f64 = f(next);
+            if f_next * f_upper <= 0.0 {
+                lower = upper;
+                f_lower = f_upper;
+            } else {
+                f_lower /= 2.0;
+            }
+            upper = next;
+            f_upper = f_next;
+        }
+        (lower / 2.0).exp()
+    }
+}
+
+fn glicko_g(phi: f64) -> f64 {
+    1.0 / (1.0 + 3.0 * phi * phi / (PI * PI)).sqrt()
+}
+
+impl RatingSystem for Glicko2Config {
+    fn initial(&self) -> PlayerRating {
+        PlayerRating {
+            rating: self.initial_rating.unwrap_or(GLICKO_INITIAL_RATING),
+            deviation: self.initial_deviation.unwrap_or(GLICKO_INITIAL_DEVIATION),
+            volatility: self.initial_volatility.unwrap_or(GLICKO_INITIAL_VOLATILITY),
+            games: 0,
+            last_played: None,
+        }
+    }
+
+    // each player plays every other team, at its average rating and deviation, in one rating period
+    fn rate(&self, teams: &[Vec<PlayerRating>], ranks: &[usize], timestamp: u64) -> Vec<Vec<PlayerRating>> {
+        let team_mus: Vec<f64> = teams
+            .iter()
+            .map(|team| average(&team.iter().map(|player| (player.rating - GLICKO_INITIAL_RATING) / GLICKO_SCALE).collect::<Vec<f64>>()))
+            .collect();
+        let team_phis: Vec<f64> = teams
+            .iter()
+            .map(|team| average(&team.iter().map(|player| self.decayed_phi(player, timestamp)).collect::<Vec<f64>>()))
+            .collect();
+        teams
+            .iter()
+            .enumerate()
+            .map(|(index, team)| {
+                team.iter()
+                    .map(|player| {
+                        let mu: f64 = (player.rating - GLICKO_INITIAL_RATING) / GLICKO_SCALE;
+                        let phi: f64 = self.decayed_phi(player, timestamp);
+                        let mut inverse_variance: f64 = 0.0;
+                        let mut improvement: f64 = 0.0;
+                        for other_index in (0..teams.len()).filter(|other_index| *other_index != index) {
+                            let g: f64 = glicko_g(team_phis[other_index]);
+                            let expected: f64 = 1.0 / (1.0 + (-g * (mu - team_mus[other_index])).exp());
+                            inverse_variance += g * g * expected * (1.0 - expected);
+                            improvement += g * (rank_score(ranks[index], ranks[other_index]) - expected);
+                        }
+                        let variance: f64 = 1.0 / inverse_variance;
+                        let volatility: f64 = self.new_volatility(phi, player.volatility, variance, variance * improvement);
+                        let pre_period_phi: f64 = (phi * phi + volatility * volatility).sqrt();
+                        let new_phi: f64 = 1.0 / (1.0 / (pre_period_phi * pre_period_phi) + 1.0 / variance).sqrt();
+                        let new_mu: f64 = mu + new_phi * new_phi * improvement;
+                        played(player, new_mu * GLICKO_SCALE + GLICKO_INITIAL_RATING, new_phi * GLICKO_SCALE, volatility, timestamp)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn is_provisional(&self, player: &PlayerRating) -> bool {
+        player.deviation > self.provisional_deviation.unwrap_or(GLICKO_PROVISIONAL_DEVIATION)
+    }
+}
+
+// TrueSkill
+
+const TRUESKILL_MU: f64 = 25.0;
+const TRUESKILL_SIGMA: f64 = 25.0 / 3.0;
+const TRUESKILL_BETA: f64 = 25.0 / 6.0;
+const TRUESKILL_TAU: f64 = 25.0 / 300.0;
+const TRUESKILL_DRAW_PROBABILITY: f64 = 0.1;
+const TRUESKILL_PROVISIONAL_SIGMA: f64 = 25.0 / 6.0;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TrueSkillConfig {
+    mu: Option<f64>,
+    sigma: Option<f64>,
+    beta: Option<f64>,                // skill difference that gives about 76% chance of winning
+    tau: Option<f64>,                 // added to sigma before every game, so ratings keep moving
+    draw_probability: Option<f64>,
+    provisional_sigma: Option<f64>,   // players above this sigma are provisional
+}
+
+impl TrueSkillConfig {
+    // (mean change, variance factor) of the winning side of a game between two teams, and of the losing side
+    fn rate_pair(&self, winners: &[PlayerRating], losers: &[PlayerRating], is_draw: bool) -> ((f64, f64), (f64, f64)) {
+        let beta: f64 = self.beta.unwrap_or(TRUESKILL_BETA);
+        let players: f64 = (winners.len() + losers.len()) as f64;
+        let variance: f64 = winners.iter().chain(losers.iter()).map(|player| player.deviation * player.deviation).sum::<f64>();
+        let c: f64 = (variance + players * beta * beta).sqrt();
+        let draw_margin: f64 = inverse_normal_cdf((self.draw_probability.unwrap_or(TRUESKILL_DRAW_PROBABILITY) + 1.0) / 2.0) * players.sqrt() * beta;
+        let t: f64 = (winners.iter().map(|player| player.rating).sum::<f64>() - losers.iter().map(|player| player.rating).sum::<f64>()) / c;
+        let e: f64 = draw_margin / c;
+        let (v, w) = if is_draw {
+            let denominator: f64 = (normal_cdf(e - t) - normal_cdf(-e - t)).max(f64::MIN_POSITIVE);
+            let v: f64 = (normal_pdf(-e - t) - normal_pdf(e - t)) / denominator;
+            (v, v * v + ((e - t) * normal_pdf(e - t) + (e + t) * normal_pdf(e + t)) / denominator)
+        } else {
+            let v: f64 = normal_pdf(t - e) / normal_cdf(t - e).max(f64::MIN_POSITIVE);
+            (v, v * (v + t - e))
+        };
+        ((v / c, w / (c * c)), (-v / c, w / (c * c)))
+    }
+}
+
+impl RatingSystem for TrueSkillConfig {
+    fn initial(&self) -> PlayerRating {
+        PlayerRating {
+            rating: self.mu.unwrap_or(TRUESKILL_MU),
+            deviation: self.sigma.unwrap_or(TRUESKILL_SIGMA),
+            volatility: 0.0,
+            games: 0,
+            last_played: None,
+        }
+    }
+
+    fn rate(&self, teams: &[Vec<PlayerRating>], ranks: &[usize], timestamp: u64) -> Vec<Vec<PlayerRating>> {
+        let tau: f64 = self.tau.unwrap_or(TRUESKILL_TAU);
+        let teams: Vec<Vec<PlayerRating>> = teams
+            .iter()
+            .map(|team| {
+                team.iter()
+                    .map(|player| PlayerRating { deviation: (player.deviation * player.deviation + tau * tau).sqrt(), ..player.clone() })
+                    .collect()
+            })
+            .collect();
+        // (sum of mean changes, sum of variance factors) per team, from each neighbouring pair in the ranking
+        let mut order: Vec<usize> = (0..teams.len()).collect();
+        order.sort_by_key(|index| ranks[*index]);
+        let mut updates: Vec<(f64, f64)> = vec![(0.0, 0.0); teams.len()];
+        for pair in order.windows(2) {
+            let (winner, loser) = (pair[0], pair[1]);
+            let ((winner_mean, winner_factor), (loser_mean, loser_factor)) =
+                self.rate_pair(&teams[winner], &teams[loser], ranks[winner] == ranks[loser]);
+            updates[winner] = (updates[winner].0 + winner_mean, updates[winner].1 + winner_factor);
+            updates[loser] = (updates[loser].0 + loser_mean, updates[loser].1 + loser_factor);
+        }
+        teams
+            .iter()
+            .zip(updates.iter())
+            .map(|(team, (mean, factor))| {
+                team.iter()
+                    .map(|player| {
+                        let variance: f64 = player.deviation * player.deviation;
+                        let rating: f64 = player.rating + variance * mean;
+                        let deviation: f64 = (variance * (1.0 - variance * factor).max(0.0001)).sqrt();
+                        played(player, rating, deviation, 0.0, timestamp)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn is_provisional(&self, player: &PlayerRating) -> bool {
+        player.deviation > self.provisional_sigma.unwrap_or(TRUESKILL_PROVISIONAL_SIGMA)
+    }
+
+    // the conservative estimate, so players are only ranked high once the system is sure of them
+    fn score(&self, player: &PlayerRating) -> f64 {
+        player.rating - 3.0 * player.deviation
+    }
+}
+
+fn normal_pdf(x: f64) -> f64 {
+    (-x * x / 2.0).exp() / (2.0 * PI).sqrt()
+}
+
+fn normal_cdf(x: f64) -> f64 {
+    erfc(-x / 2f64.sqrt()) / 2.0
+}
+
+// complementary error function, accurate to 1.2e-7 (Numerical Recipes, erfcc)
+fn erfc(x: f64) -> f64 {
+    let z: f64 = x.abs();
+    let t: f64 = 1.0 / (1.0 + z / 2.0);
+    let coefficients: [f64; 10] = [
+        -1.26551223, 1.00002368, 0.37409196, 0.09678418, -0.18628806,
+        0.27886807, -1.13520398, 1.48851587, -0.82215223, 0.17087277,
+    ];
+    let polynomial: f64 = coefficients.iter().rev().fold(0.0, |sum, coefficient| sum * t + coefficient);
+    let result: f64 = t * (-z * z + polynomial).exp();
+    if x >= 0.0 { result } else { 2.0 - result }
+}
+
+// inverse of normal_cdf by bisection, plenty for a draw margin computed once per game
+fn inverse_normal_cdf(p: f64) -> f64 {
+    let (mut lower, mut upper) = (-10.0, 10.0);
+    for _ in 0..100 {
+        let middle: f64 = (lower + upper) / 2.0;
+        if normal_cdf(middle) < p {
+            lower = middle;
+        } else {
+            upper = middle;
+        }
+    }
+    (lower + upper) / 2.0
+}
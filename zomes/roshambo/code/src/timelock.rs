@@ -0,0 +1,53 @@
+// Rivest-Shamir-Wagner time-lock puzzles
+// The key of a puzzle is base^(2^squarings) mod modulus. Whoever chose the modulus knows its totient
+// and can compute the key with two modular exponentiations; everyone else has to do the squarings
+// one after another, which takes a predictable amount of time no matter how much hardware they have.
+
+use multihash::{encode, Hash as Multihash};
+use num_bigint::BigUint;
+use num_traits::Zero;
+
+pub fn parse_number(number: &str) -> Result<BigUint, String> {
+    match BigUint::parse_bytes(number.as_bytes(), 10) {
+        Some(parsed) => Ok(parsed),
+        None => Err(format!("Not a decimal number: {}", number)),
+    }
+}
+
+pub fn fast_key(modulus: &BigUint, totient: &BigUint, base: &BigUint, squarings: u64) -> Result<BigUint, String> {
+    if modulus.is_zero() || totient.is_zero() {
+        return Err(String::from("Time-lock modulus and totient must be positive"));
+    }
+    let exponent = BigUint::from(2u32).modpow(&BigUint::from(squarings), totient);
+    Ok(base.modpow(&exponent, modulus))
+}
+
+pub fn solve(modulus: &BigUint, base: &BigUint, squarings: u64) -> Result<BigUint, String> {
+    if modulus.is_zero() {
+        return Err(String::from("Time-lock modulus must be positive"));
+    }
+    let mut key = base % modulus;
+    for _ in 0..squarings {
+        key = (&key * &key) % modulus;
+    }
+    Ok(key)
+}
+
+// xors data with a SHA2-256 keystream of (key || block index), so sealing and unsealing are the same
+pub fn apply_key(key: &BigUint, data: &[u8]) -> Result<Vec<u8>, String> {
+    let key_bytes: Vec<u8> = key.to_bytes_be();
+    let mut output: Vec<u8> = Vec::with_capacity(data.len());
+    for (block_index, block) in data.chunks(32).enumerate() {
+        let mut input: Vec<u8> = key_bytes.clone();
+        for shift in (0..8).rev() {
+            input.push((block_index as u64 >> (shift * 8)) as u8);
+        }
+        // strip the two byte multihash prefix to get the bare digest
+        let keystream = match encode(Multihash::SHA2256, &input) {
+            Ok(multihash) => multihash[2..].to_vec(),
+            Err(_) => return Err(String::from("Could not hash time-lock keystream")),
+        };
+        output.extend(block.iter().zip(keystream.iter()).map(|(byte, key_byte)| byte ^ key_byte));
+    }
+    Ok(output)
+}